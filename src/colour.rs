@@ -0,0 +1,443 @@
+use crate::pixel_formats::*;
+
+// An RGB colour with alpha, using 8 bits per component
+// alpha of 255 is fully opaque, and 0 is fully transparent
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash)]
+pub struct Colour {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Colour {
+    pub fn new(r: u8, g: u8, b: u8, a: u8) -> Colour {
+        Colour { r, g, b, a }
+    }
+
+    pub fn opaque(r: u8, g: u8, b: u8) -> Colour {
+        Colour { r, g, b, a: 255 }
+    }
+
+    // composite this colour over `dest` using the "source over" operator
+    // `global_alpha` scales the alpha of this colour, allowing for fades
+    pub fn blend_over(self, dest: Colour, global_alpha: u8) -> Colour {
+        let alpha = mul_u8(self.a, global_alpha) as u32;
+        if alpha == 255 {
+            return Colour { a: 255, ..self };
+        }
+        if alpha == 0 {
+            return dest;
+        }
+        // weight of the destination, after being covered by the source
+        let dest_weight = dest.a as u32 * (255 - alpha) / 255;
+        let out_alpha = alpha + dest_weight;
+        let mix = |source: u8, dest: u8| -> u8 {
+            ((source as u32 * alpha + dest as u32 * dest_weight + out_alpha / 2) / out_alpha) as u8
+        };
+        Colour {
+            r: mix(self.r, dest.r),
+            g: mix(self.g, dest.g),
+            b: mix(self.b, dest.b),
+            a: out_alpha as u8,
+        }
+    }
+
+    // squared distance between the RGB components of two colours
+    pub fn distance(self, other: Colour) -> u32 {
+        let diff = |a: u8, b: u8| -> u32 {
+            let d = a.abs_diff(b) as u32;
+            d * d
+        };
+        diff(self.r, other.r) + diff(self.g, other.g) + diff(self.b, other.b)
+    }
+
+    // index of the palette entry closest to this colour, ignoring alpha
+    pub fn nearest_index(self, palette: &[Colour]) -> usize {
+        palette
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, entry)| self.distance(**entry))
+            .map(|(index, _)| index)
+            .unwrap_or(0)
+    }
+}
+
+// multiply two 0-255 values, treating 255 as 1.0
+#[inline]
+fn mul_u8(a: u8, b: u8) -> u8 {
+    ((a as u32 * b as u32 + 127) / 255) as u8
+}
+
+// expand an n-bit colour component out to 8 bits
+#[inline]
+fn expand(value: u32, bits: u32) -> u8 {
+    let max = (1 << bits) - 1;
+    ((value & max) * 255 / max) as u8
+}
+
+// reduce an 8-bit colour component down to n bits, rounding to the nearest value
+#[inline]
+fn reduce(value: u8, bits: u32) -> u32 {
+    let max = (1 << bits) - 1;
+    (value as u32 * max + 127) / 255
+}
+
+// pixel formats that directly store a colour
+pub trait ColourPixel: PixelChunk {
+    fn to_colour(self) -> Colour;
+
+    fn from_colour(colour: Colour) -> Self;
+}
+
+impl ColourPixel for Argb8888 {
+    fn to_colour(self) -> Colour {
+        Colour {
+            a: (self.value >> 24) as u8,
+            r: (self.value >> 16) as u8,
+            g: (self.value >> 8) as u8,
+            b: self.value as u8,
+        }
+    }
+
+    fn from_colour(colour: Colour) -> Self {
+        Argb8888 {
+            value: (colour.a as u32) << 24
+                | (colour.r as u32) << 16
+                | (colour.g as u32) << 8
+                | colour.b as u32,
+        }
+    }
+}
+
+impl ColourPixel for Argb4444 {
+    fn to_colour(self) -> Colour {
+        let value = self.value as u32;
+        Colour {
+            a: expand(value >> 12, 4),
+            r: expand(value >> 8, 4),
+            g: expand(value >> 4, 4),
+            b: expand(value, 4),
+        }
+    }
+
+    fn from_colour(colour: Colour) -> Self {
+        Argb4444 {
+            value: (reduce(colour.a, 4) << 12
+                | reduce(colour.r, 4) << 8
+                | reduce(colour.g, 4) << 4
+                | reduce(colour.b, 4)) as u16,
+        }
+    }
+}
+
+impl ColourPixel for Rgba2222 {
+    fn to_colour(self) -> Colour {
+        let value = self.value as u32;
+        Colour {
+            r: expand(value, 2),
+            g: expand(value >> 2, 2),
+            b: expand(value >> 4, 2),
+            a: expand(value >> 6, 2),
+        }
+    }
+
+    fn from_colour(colour: Colour) -> Self {
+        Rgba2222 {
+            value: (reduce(colour.r, 2)
+                | reduce(colour.g, 2) << 2
+                | reduce(colour.b, 2) << 4
+                | reduce(colour.a, 2) << 6) as u8,
+        }
+    }
+}
+
+// pixel formats that store an index into a palette
+pub trait IndexedPixel: PixelChunk {
+    // the number of palette entries a pixel can address
+    fn colours() -> usize;
+
+    fn to_index(self) -> usize;
+
+    fn from_index(index: usize) -> Self;
+}
+
+impl IndexedPixel for Pixel8 {
+    fn colours() -> usize {
+        256
+    }
+
+    fn to_index(self) -> usize {
+        self.value as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        (index as u8).into()
+    }
+}
+
+impl IndexedPixel for Pixel4 {
+    fn colours() -> usize {
+        16
+    }
+
+    // NB as with other single pixel uses of Pixel4, the lowest 4 bits hold the pixel
+    fn to_index(self) -> usize {
+        (self.value & 0xF) as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        ((index & 0xF) as u8).into()
+    }
+}
+
+impl IndexedPixel for Pixel2 {
+    fn colours() -> usize {
+        4
+    }
+
+    fn to_index(self) -> usize {
+        (self.value & 0x3) as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        ((index & 0x3) as u8).into()
+    }
+}
+
+impl IndexedPixel for Pixel1 {
+    fn colours() -> usize {
+        2
+    }
+
+    fn to_index(self) -> usize {
+        (self.value & 0x1) as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        ((index & 0x1) as u8).into()
+    }
+}
+
+// Converts between the pixels of a destination and colours when blending
+pub trait ColourMap<T> {
+    fn colour(&self, pixel: T) -> Colour;
+
+    fn pixel(&self, colour: Colour) -> T;
+}
+
+// Colour map for formats that directly store a colour
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DirectColour;
+
+impl<T: ColourPixel> ColourMap<T> for DirectColour {
+    fn colour(&self, pixel: T) -> Colour {
+        pixel.to_colour()
+    }
+
+    fn pixel(&self, colour: Colour) -> T {
+        T::from_colour(colour)
+    }
+}
+
+// Colour map for palette-indexed formats
+// blended colours are converted back to the nearest matching palette entry
+#[derive(Debug, Clone, Copy)]
+pub struct IndexedColour<'a> {
+    pub palette: &'a [Colour],
+}
+
+impl<'a> IndexedColour<'a> {
+    pub fn new(palette: &'a [Colour]) -> IndexedColour<'a> {
+        IndexedColour { palette }
+    }
+}
+
+impl<T: IndexedPixel> ColourMap<T> for IndexedColour<'_> {
+    fn colour(&self, pixel: T) -> Colour {
+        self.palette
+            .get(pixel.to_index())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    // only the entries the pixel format can address are searched, so a larger palette can be shared
+    fn pixel(&self, colour: Colour) -> T {
+        let palette = &self.palette[..self.palette.len().min(T::colours())];
+        T::from_index(colour.nearest_index(palette))
+    }
+}
+
+// A lookup table to remap the pixel values of a byte-sized chunk format
+// the table is expanded to cover every possible chunk value, so chunks are remapped in one step
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColourLut<T: ByteChunk> {
+    chunks: Vec<T>,
+}
+
+impl<T: ByteChunk<PixelType = T>> ColourLut<T> {
+    // build a table where each pixel is remapped by calling `map` with its value
+    pub fn new<F: Fn(T) -> T>(map: F) -> ColourLut<T> {
+        let chunks = (0..=255u8)
+            .map(|value| {
+                let mut chunk = T::from_byte(value);
+                for i in 0..T::pixels() {
+                    let pixel = chunk.get_pixel(i).unwrap();
+                    chunk.set_pixel(i, map(pixel));
+                }
+                chunk
+            })
+            .collect();
+        ColourLut { chunks }
+    }
+
+    // build a table from a list of new pixel values, indexed by the current pixel value
+    // pixel values with no entry in `table` are left unchanged
+    pub fn from_table(table: &[T]) -> ColourLut<T> {
+        Self::new(|pixel| {
+            table
+                .get(pixel.to_byte() as usize)
+                .copied()
+                .unwrap_or(pixel)
+        })
+    }
+
+    // build a table that replaces one pixel value with another
+    pub fn replacing(from: T, to: T) -> ColourLut<T> {
+        let from = T::filled_pixel(from).get_pixel(0).unwrap();
+        Self::new(|pixel| if pixel == from { to } else { pixel })
+    }
+
+    #[inline]
+    pub fn map_chunk(&self, chunk: T) -> T {
+        self.chunks[chunk.to_byte() as usize]
+    }
+}
+
+// Settings for alpha blending into a destination
+#[derive(Debug, Clone, Copy)]
+pub struct Blend<M> {
+    pub map: M,
+    // constant alpha applied on top of the alpha of each source colour
+    pub global_alpha: u8,
+}
+
+impl<M> Blend<M> {
+    pub fn new(map: M) -> Blend<M> {
+        Blend {
+            map,
+            global_alpha: 255,
+        }
+    }
+
+    pub fn with_global_alpha(self, global_alpha: u8) -> Blend<M> {
+        Blend {
+            global_alpha,
+            ..self
+        }
+    }
+
+    // blend `colour` over the `dest` pixel, returning the resulting pixel
+    pub fn blend<T>(&self, colour: Colour, dest: T) -> T
+    where
+        M: ColourMap<T>,
+    {
+        self.map
+            .pixel(colour.blend_over(self.map.colour(dest), self.global_alpha))
+    }
+
+    // whether blending `colour` will fully replace the destination
+    pub fn is_opaque(&self, colour: Colour) -> bool {
+        mul_u8(colour.a, self.global_alpha) == 255
+    }
+
+    // whether blending `colour` will leave the destination unchanged
+    pub fn is_transparent(&self, colour: Colour) -> bool {
+        mul_u8(colour.a, self.global_alpha) == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_blend_colours() {
+        let red = Colour::opaque(255, 0, 0);
+        let blue = Colour::opaque(0, 0, 255);
+        assert_eq!(red.blend_over(blue, 255), red);
+        assert_eq!(red.blend_over(blue, 0), blue);
+        assert_eq!(
+            Colour::new(255, 0, 0, 128).blend_over(blue, 255),
+            Colour::new(128, 0, 127, 255)
+        );
+        // global alpha scales the source alpha
+        assert_eq!(red.blend_over(blue, 128), Colour::new(128, 0, 127, 255));
+        // blending over a transparent destination keeps the source colour
+        assert_eq!(
+            Colour::new(255, 0, 0, 128).blend_over(Colour::default(), 255),
+            Colour::new(255, 0, 0, 128)
+        );
+    }
+
+    #[test]
+    fn can_convert_colour_formats() {
+        let colour = Colour::new(0x12, 0x34, 0x56, 0x78);
+        assert_eq!(Argb8888::from_colour(colour).value, 0x78123456);
+        assert_eq!(Argb8888::from_colour(colour).to_colour(), colour);
+
+        let pixel: Argb4444 = 0xF80F.into();
+        assert_eq!(pixel.to_colour(), Colour::new(0x88, 0x00, 0xFF, 0xFF));
+        assert_eq!(Argb4444::from_colour(pixel.to_colour()), pixel);
+
+        let pixel: Rgba2222 = 0b11_10_01_00.into();
+        assert_eq!(pixel.to_colour(), Colour::new(0, 85, 170, 255));
+        assert_eq!(Rgba2222::from_colour(pixel.to_colour()), pixel);
+    }
+
+    #[test]
+    fn can_find_nearest_palette_entry() {
+        let palette = [
+            Colour::opaque(0, 0, 0),
+            Colour::opaque(255, 0, 0),
+            Colour::opaque(0, 255, 0),
+            Colour::opaque(255, 255, 255),
+        ];
+        assert_eq!(Colour::opaque(200, 10, 10).nearest_index(&palette), 1);
+        assert_eq!(Colour::opaque(200, 200, 200).nearest_index(&palette), 3);
+        assert_eq!(Colour::opaque(20, 20, 20).nearest_index(&palette), 0);
+
+        let blend = Blend::new(IndexedColour::new(&palette));
+        let pixel: Pixel4 = blend.blend(Colour::new(255, 255, 255, 200), Pixel4::from(0));
+        assert_eq!(pixel.to_index(), 3);
+        let pixel: Pixel4 = blend.blend(Colour::new(255, 255, 255, 50), Pixel4::from(0));
+        assert_eq!(pixel.to_index(), 0);
+    }
+
+    #[test]
+    fn only_searches_addressable_palette_entries() {
+        let mut palette = [Colour::opaque(0, 0, 0); 64];
+        palette[1] = Colour::opaque(255, 255, 255);
+        palette[5] = Colour::opaque(128, 0, 0);
+        palette[17] = Colour::opaque(255, 0, 0);
+        let blend = Blend::new(IndexedColour::new(&palette));
+        // red can't be stored in 4 bits, so the nearest colour within reach is used
+        let pixel: Pixel4 = blend.blend(Colour::opaque(255, 0, 0), Pixel4::from(0));
+        assert_eq!(pixel.to_index(), 5);
+        let pixel: Pixel8 = blend.blend(Colour::opaque(255, 0, 0), Pixel8::from(0));
+        assert_eq!(pixel.to_index(), 17);
+    }
+
+    #[test]
+    fn can_remap_chunks_with_lut() {
+        let lut: ColourLut<Pixel4> = ColourLut::from_table(&[0xF.into(), 0xE.into()]);
+        assert_eq!(lut.map_chunk(0x01.into()), 0xFE.into());
+        assert_eq!(lut.map_chunk(0x12.into()), 0xE2.into());
+
+        let lut: ColourLut<Pixel2> = ColourLut::replacing(1.into(), 3.into());
+        assert_eq!(lut.map_chunk(0b01_10_01_00.into()), 0b11_10_11_00.into());
+
+        let lut: ColourLut<Pixel8> = ColourLut::new(|pixel: Pixel8| (pixel.value / 2).into());
+        assert_eq!(lut.map_chunk(200.into()), 100.into());
+    }
+}
@@ -0,0 +1,64 @@
+use std::fmt;
+
+// Errors returned by framebuffer operations
+//
+// Drawing operations never panic on bad coordinates.  The plain versions silently clip to
+// the row or framebuffer, writing whatever part of the operation lands inside it, so a
+// stray coordinate can't crash the display.  Each has a `try_` version that instead checks
+// its coordinates up front and returns one of these errors, leaving the pixels untouched,
+// along with any state passed in such as the phase of a dotted line pattern.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Error {
+    // a source slice didn't hold the number of pixels needed
+    SourceLength {
+        expected: usize,
+        actual: usize,
+    },
+    // a pixel index or coordinate was outside of a row or framebuffer of `length` pixels
+    OutOfBounds {
+        index: usize,
+        length: usize,
+    },
+    // a range was reversed, or extended past the end of a row or framebuffer of `length` pixels
+    InvalidRange {
+        start: usize,
+        end: usize,
+        length: usize,
+    },
+    // a range needed to start on a chunk boundary, but started at pixel `index`
+    Unaligned {
+        index: usize,
+    },
+    // entry `index` of a slice of single pixel values had bits set beyond the width of one pixel
+    InvalidPixel {
+        index: usize,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::SourceLength { expected, actual } => {
+                write!(f, "expected {} source pixels, got {}", expected, actual)
+            }
+            Error::OutOfBounds { index, length } => {
+                write!(f, "index {} is out of bounds for length {}", index, length)
+            }
+            Error::InvalidRange { start, end, length } => {
+                write!(
+                    f,
+                    "range {}..{} is invalid for length {}",
+                    start, end, length
+                )
+            }
+            Error::Unaligned { index } => {
+                write!(f, "index {} is not on a chunk boundary", index)
+            }
+            Error::InvalidPixel { index } => {
+                write!(f, "source entry {} is not a single pixel value", index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -1,13 +1,59 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 use std::ops::{Index, Range};
 
-use crate::pixel_formats::PixelChunk;
-use crate::pixel_row::PixelRow;
+use crate::colour::{Blend, Colour, ColourLut, ColourMap, ColourPixel};
+use crate::error::Error;
+use crate::pattern::{LinePattern, Pattern};
+use crate::pixel_formats::{ByteChunk, Pixel1, PixelChunk};
+use crate::pixel_row::{Align, PixelRow, WriteMask};
+use crate::plot_mode::PlotMode;
 
+// The corner, edge or centre of a framebuffer that existing pixels are kept against when it is resized
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Centre,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    // the horizontal and vertical alignment of the anchor
+    pub fn align(self) -> (Align, Align) {
+        match self {
+            Anchor::TopLeft => (Align::Start, Align::Start),
+            Anchor::Top => (Align::Centre, Align::Start),
+            Anchor::TopRight => (Align::End, Align::Start),
+            Anchor::Left => (Align::Start, Align::Centre),
+            Anchor::Centre => (Align::Centre, Align::Centre),
+            Anchor::Right => (Align::End, Align::Centre),
+            Anchor::BottomLeft => (Align::Start, Align::End),
+            Anchor::Bottom => (Align::Centre, Align::End),
+            Anchor::BottomRight => (Align::End, Align::End),
+        }
+    }
+}
+
+// Framebuffers compare and hash by their visible pixels only
+// so padding, scroll position, write masks and clip masks are ignored
+// Clones share row storage, with each row only copied when it is first changed
 #[allow(dead_code)]
+#[derive(Clone)]
 pub struct FrameBuffer<T: PixelChunk> {
     width: usize,
     height: usize,
     rows: Vec<PixelRow<T>>,
+    // bits of each pixel value that writes are allowed to change
+    write_mask: T,
+    // optional 1bpp mask, where writes only land on pixels that are set in the mask
+    clip_mask: Option<Box<FrameBuffer<Pixel1>>>,
 }
 
 impl<T: PixelChunk<PixelType = T>> FrameBuffer<T> {
@@ -16,6 +62,8 @@ impl<T: PixelChunk<PixelType = T>> FrameBuffer<T> {
             width,
             height,
             rows: vec![PixelRow::new(width); height],
+            write_mask: !T::default(),
+            clip_mask: None,
         }
     }
 
@@ -24,16 +72,723 @@ impl<T: PixelChunk<PixelType = T>> FrameBuffer<T> {
             width,
             height,
             rows: vec![PixelRow::new_with(width, pixel); height],
+            write_mask: !T::default(),
+            clip_mask: None,
         }
     }
 
+    // create a framebuffer with `width` visible pixels on each row, stored in at least `total_width` pixels
+    // allowing smooth horizontal scrolling with `set_scroll_x`
+    pub fn new_scrollable(width: usize, height: usize, total_width: usize) -> FrameBuffer<T> {
+        FrameBuffer {
+            width,
+            height,
+            rows: vec![PixelRow::new_scrollable(width, total_width); height],
+            write_mask: !T::default(),
+            clip_mask: None,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // horizontal scroll offset of the visible area into the stored pixels
+    pub fn scroll_x(&self) -> usize {
+        self.rows.first().map_or(0, |row| row.scroll_offset())
+    }
+
+    pub fn max_scroll_x(&self) -> usize {
+        self.rows.first().map_or(0, |row| row.max_scroll_offset())
+    }
+
+    // scroll every row to `offset`, clamped to the stored pixels
+    pub fn set_scroll_x(&mut self, offset: usize) {
+        for row in self.rows.iter_mut() {
+            row.set_scroll_offset(offset);
+        }
+    }
+
+    pub fn scroll_x_by(&mut self, delta: isize) {
+        self.set_scroll_x(self.scroll_x().saturating_add_signed(delta));
+    }
+
     pub fn row(&self, y: usize) -> Option<&PixelRow<T>> {
         self.rows.get(y)
     }
 
+    // copy pixels from `source_range` of a row into row `y`, starting at `x`
+    // rows aren't handed out mutably, so that every write goes through the write mask and clip mask
+    pub fn copy_row_from(
+        &mut self,
+        x: usize,
+        y: usize,
+        source: &PixelRow<T>,
+        source_range: Range<usize>,
+    ) {
+        self.combine_row_from(x, y, source, source_range, PlotMode::Set);
+    }
+
+    pub fn combine_row_from(
+        &mut self,
+        x: usize,
+        y: usize,
+        source: &PixelRow<T>,
+        source_range: Range<usize>,
+        mode: PlotMode,
+    ) {
+        self.write_row(y, |row, write_mask| {
+            row.combine(x, source, source_range, mode, write_mask, None);
+        });
+    }
+
     pub fn pixel(&self, x: usize, y: usize) -> Option<T> {
         self.rows.get(y).and_then(|row| row.pixel(x))
     }
+
+    pub fn write_mask(&self) -> T {
+        self.write_mask
+    }
+
+    // set which bits of each pixel value writes to this framebuffer will change
+    // this works like a VGA plane mask, allowing individual bitplanes to be updated
+    pub fn set_write_mask(&mut self, write_mask: T) {
+        self.write_mask = write_mask;
+    }
+
+    pub fn clip_mask(&self) -> Option<&FrameBuffer<Pixel1>> {
+        self.clip_mask.as_deref()
+    }
+
+    pub fn clip_mask_mut(&mut self) -> Option<&mut FrameBuffer<Pixel1>> {
+        self.clip_mask.as_deref_mut()
+    }
+
+    // attach a 1bpp mask, aligned with the top left of this framebuffer
+    // all writes will only land on pixels that are set in the mask
+    // pixels outside of the mask are treated as not set
+    pub fn set_clip_mask(&mut self, clip_mask: Option<FrameBuffer<Pixel1>>) {
+        self.clip_mask = clip_mask.map(Box::new);
+    }
+
+    // set a pixel, ignoring coordinates outside of the framebuffer
+    pub fn set_pixel(&mut self, x: usize, y: usize, pixel: T) {
+        self.plot_pixel(x, y, pixel, PlotMode::Set);
+    }
+
+    pub fn fill_range(&mut self, y: usize, range: Range<usize>, pixel: T) {
+        self.plot_range(y, range, pixel, PlotMode::Set);
+    }
+
+    pub fn plot_pixel(&mut self, x: usize, y: usize, pixel: T, mode: PlotMode) {
+        self.write_row(y, |row, write_mask| {
+            row.write_pixel(x, pixel, mode, write_mask);
+        });
+    }
+
+    pub fn plot_range(&mut self, y: usize, range: Range<usize>, pixel: T, mode: PlotMode) {
+        self.write_row(y, |row, write_mask| {
+            row.write_range(range, T::filled_pixel(pixel), mode, write_mask);
+        });
+    }
+
+    // plot a pixel if the next pixel of a dotted line pattern is on
+    pub fn plot_pixel_dotted(
+        &mut self,
+        x: usize,
+        y: usize,
+        pixel: T,
+        mode: PlotMode,
+        pattern: &mut LinePattern,
+    ) {
+        let mut written = false;
+        self.write_row(y, |row, write_mask| {
+            row.write_pixel_dotted(x, pixel, mode, pattern, write_mask);
+            written = true;
+        });
+        // keep the pattern in step even when the pixel is off screen
+        if !written {
+            pattern.skip(1);
+        }
+    }
+
+    // plot a range of a row from left to right, only where a dotted line pattern is on
+    pub fn plot_range_dotted(
+        &mut self,
+        y: usize,
+        range: Range<usize>,
+        pixel: T,
+        mode: PlotMode,
+        pattern: &mut LinePattern,
+    ) {
+        let length = range.len();
+        let mut written = false;
+        self.write_row(y, |row, write_mask| {
+            row.write_range_dotted(range, pixel, mode, pattern, write_mask);
+            written = true;
+        });
+        if !written {
+            pattern.skip(length);
+        }
+    }
+
+    // fill a range of a row with a repeating pattern, aligned with the origin of the framebuffer
+    pub fn fill_range_with_pattern(&mut self, y: usize, range: Range<usize>, pattern: &Pattern<T>) {
+        self.plot_range_with_pattern(y, range, pattern, PlotMode::Set);
+    }
+
+    pub fn plot_range_with_pattern(
+        &mut self,
+        y: usize,
+        range: Range<usize>,
+        pattern: &Pattern<T>,
+        mode: PlotMode,
+    ) {
+        self.write_row(y, |row, write_mask| {
+            row.write_range_with_pattern(range, pattern, y, mode, write_mask);
+        });
+    }
+
+    // fill a rectangle with a repeating pattern, aligned with the origin of the framebuffer
+    pub fn fill_rect_with_pattern(
+        &mut self,
+        x: Range<usize>,
+        y: Range<usize>,
+        pattern: &Pattern<T>,
+    ) {
+        self.plot_rect_with_pattern(x, y, pattern, PlotMode::Set);
+    }
+
+    pub fn plot_rect_with_pattern(
+        &mut self,
+        x: Range<usize>,
+        y: Range<usize>,
+        pattern: &Pattern<T>,
+        mode: PlotMode,
+    ) {
+        for row in clip(y, self.height) {
+            self.plot_range_with_pattern(row, x.clone(), pattern, mode);
+        }
+    }
+
+    // replace every pixel in a rectangle that matches `from` with `to`
+    pub fn replace_colour(&mut self, x: Range<usize>, y: Range<usize>, from: T, to: T) {
+        for row in clip(y, self.height) {
+            self.write_row(row, |row, write_mask| {
+                row.write_replace_colour(x.clone(), from, to, write_mask);
+            });
+        }
+    }
+
+    // replace every pixel in the framebuffer that matches `from` with `to`
+    pub fn replace_colour_all(&mut self, from: T, to: T) {
+        self.replace_colour(0..self.width, 0..self.height, from, to);
+    }
+
+    // alpha blend `colour` over a pixel
+    pub fn blend_pixel<M: ColourMap<T>>(
+        &mut self,
+        x: usize,
+        y: usize,
+        colour: Colour,
+        blend: &Blend<M>,
+    ) {
+        self.blend_range(y, x..x.saturating_add(1), colour, blend);
+    }
+
+    // alpha blend `colour` over a range of pixels in a row
+    pub fn blend_range<M: ColourMap<T>>(
+        &mut self,
+        y: usize,
+        range: Range<usize>,
+        colour: Colour,
+        blend: &Blend<M>,
+    ) {
+        self.write_row(y, |row, write_mask| {
+            row.blend_range_through(range, colour, blend, write_mask);
+        });
+    }
+
+    // alpha blend a rectangle from a source framebuffer with alpha so that its top left corner lands at `x`, `y`
+    pub fn blend_rect_from<S, M>(
+        &mut self,
+        x: usize,
+        y: usize,
+        source: &FrameBuffer<S>,
+        source_x: Range<usize>,
+        source_y: Range<usize>,
+        blend: &Blend<M>,
+    ) where
+        S: ColourPixel<PixelType = S>,
+        M: ColourMap<T>,
+    {
+        for (dest_y, source_row) in (y..self.height).zip(source.rows_in(source_y)) {
+            self.write_row(dest_y, |row, write_mask| {
+                row.blend_from_through(x, source_row, source_x.clone(), blend, write_mask);
+            });
+        }
+    }
+
+    // copy a rectangle from `source` so that its top left corner lands at `x`, `y`
+    // the copy is clipped to both framebuffers
+    pub fn copy_rect_from(
+        &mut self,
+        x: usize,
+        y: usize,
+        source: &FrameBuffer<T>,
+        source_x: Range<usize>,
+        source_y: Range<usize>,
+    ) {
+        self.combine_rect(x, y, source, source_x, source_y, PlotMode::Set, None);
+    }
+
+    // copy a rectangle from `source`, leaving the destination untouched where the source pixel is `key`
+    pub fn copy_rect_from_keyed(
+        &mut self,
+        x: usize,
+        y: usize,
+        source: &FrameBuffer<T>,
+        source_x: Range<usize>,
+        source_y: Range<usize>,
+        key: T,
+    ) {
+        self.combine_rect(x, y, source, source_x, source_y, PlotMode::Set, Some(key));
+    }
+
+    pub fn combine_rect_from(
+        &mut self,
+        x: usize,
+        y: usize,
+        source: &FrameBuffer<T>,
+        source_x: Range<usize>,
+        source_y: Range<usize>,
+        mode: PlotMode,
+    ) {
+        self.combine_rect(x, y, source, source_x, source_y, mode, None);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn combine_rect(
+        &mut self,
+        x: usize,
+        y: usize,
+        source: &FrameBuffer<T>,
+        source_x: Range<usize>,
+        source_y: Range<usize>,
+        mode: PlotMode,
+        key: Option<T>,
+    ) {
+        for (dest_y, source_row) in (y..self.height).zip(source.rows_in(source_y)) {
+            self.write_row(dest_y, |row, write_mask| {
+                row.combine(x, source_row, source_x.clone(), mode, write_mask, key);
+            });
+        }
+    }
+
+    // change the size of the framebuffer, keeping its pixels against `anchor`
+    // pixels that no longer fit are lost, and new pixels are set to `fill`
+    // rows are reallocated without any scroll padding, and the clip mask is left as it is
+    pub fn resize(&mut self, width: usize, height: usize, anchor: Anchor, fill: T) {
+        let (align_x, align_y) = anchor.align();
+        let offset = align_y.offset(self.height, height);
+        // skip any rows lost from the top, and keep the rest below any rows added at the top
+        let mut old_rows = std::mem::take(&mut self.rows)
+            .into_iter()
+            .skip((-offset).max(0) as usize);
+        let added = offset.max(0) as usize;
+        self.rows = (0..height)
+            .map(|y| match (y >= added).then(|| old_rows.next()).flatten() {
+                Some(mut row) => {
+                    row.resize(width, align_x, fill);
+                    row
+                }
+                None => PixelRow::new_with(width, T::filled_pixel(fill)),
+            })
+            .collect();
+        self.width = width;
+        self.height = height;
+    }
+
+    // checks that `x`, `y` is a pixel within the framebuffer
+    pub fn check_point(&self, x: usize, y: usize) -> Result<(), Error> {
+        if x >= self.width {
+            Err(Error::OutOfBounds {
+                index: x,
+                length: self.width,
+            })
+        } else if y >= self.height {
+            Err(Error::OutOfBounds {
+                index: y,
+                length: self.height,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    // checks that the rectangle `x`, `y` is in order and lies within the framebuffer
+    pub fn check_rect(&self, x: &Range<usize>, y: &Range<usize>) -> Result<(), Error> {
+        check_range(x, self.width).and(check_range(y, self.height))
+    }
+
+    // the rows in `range` that exist in this framebuffer
+    fn rows_in(&self, range: Range<usize>) -> impl Iterator<Item = &PixelRow<T>> {
+        self.rows.iter().take(range.end).skip(range.start)
+    }
+
+    // calls `write` with row `y` and the write mask that applies to it
+    // rows that don't exist, or that are entirely outside of the clip mask, are skipped
+    pub(crate) fn write_row<F>(&mut self, y: usize, write: F)
+    where
+        F: FnOnce(&mut PixelRow<T>, &WriteMask<T>),
+    {
+        let Some(row) = self.rows.get_mut(y) else {
+            return;
+        };
+        let write_mask = WriteMask::new(self.write_mask);
+        match &self.clip_mask {
+            Some(clip_mask) => {
+                if let Some(stencil) = clip_mask.rows.get(y) {
+                    write(row, &write_mask.with_stencil(stencil));
+                }
+            }
+            None => write(row, &write_mask),
+        }
+    }
+}
+
+// Checked versions of the framebuffer drawing operations, following the policy on `Error`
+impl<T: PixelChunk<PixelType = T>> FrameBuffer<T> {
+    pub fn try_set_pixel(&mut self, x: usize, y: usize, pixel: T) -> Result<(), Error> {
+        self.try_plot_pixel(x, y, pixel, PlotMode::Set)
+    }
+
+    pub fn try_fill_range(&mut self, y: usize, range: Range<usize>, pixel: T) -> Result<(), Error> {
+        self.try_plot_range(y, range, pixel, PlotMode::Set)
+    }
+
+    pub fn try_plot_pixel(
+        &mut self,
+        x: usize,
+        y: usize,
+        pixel: T,
+        mode: PlotMode,
+    ) -> Result<(), Error> {
+        self.check_point(x, y)?;
+        self.plot_pixel(x, y, pixel, mode);
+        Ok(())
+    }
+
+    pub fn try_plot_range(
+        &mut self,
+        y: usize,
+        range: Range<usize>,
+        pixel: T,
+        mode: PlotMode,
+    ) -> Result<(), Error> {
+        self.check_span(y, &range)?;
+        self.plot_range(y, range, pixel, mode);
+        Ok(())
+    }
+
+    pub fn try_plot_pixel_dotted(
+        &mut self,
+        x: usize,
+        y: usize,
+        pixel: T,
+        mode: PlotMode,
+        pattern: &mut LinePattern,
+    ) -> Result<(), Error> {
+        self.check_point(x, y)?;
+        self.plot_pixel_dotted(x, y, pixel, mode, pattern);
+        Ok(())
+    }
+
+    pub fn try_plot_range_dotted(
+        &mut self,
+        y: usize,
+        range: Range<usize>,
+        pixel: T,
+        mode: PlotMode,
+        pattern: &mut LinePattern,
+    ) -> Result<(), Error> {
+        self.check_span(y, &range)?;
+        self.plot_range_dotted(y, range, pixel, mode, pattern);
+        Ok(())
+    }
+
+    pub fn try_fill_range_with_pattern(
+        &mut self,
+        y: usize,
+        range: Range<usize>,
+        pattern: &Pattern<T>,
+    ) -> Result<(), Error> {
+        self.try_plot_range_with_pattern(y, range, pattern, PlotMode::Set)
+    }
+
+    pub fn try_plot_range_with_pattern(
+        &mut self,
+        y: usize,
+        range: Range<usize>,
+        pattern: &Pattern<T>,
+        mode: PlotMode,
+    ) -> Result<(), Error> {
+        self.check_span(y, &range)?;
+        self.plot_range_with_pattern(y, range, pattern, mode);
+        Ok(())
+    }
+
+    pub fn try_fill_rect_with_pattern(
+        &mut self,
+        x: Range<usize>,
+        y: Range<usize>,
+        pattern: &Pattern<T>,
+    ) -> Result<(), Error> {
+        self.try_plot_rect_with_pattern(x, y, pattern, PlotMode::Set)
+    }
+
+    pub fn try_plot_rect_with_pattern(
+        &mut self,
+        x: Range<usize>,
+        y: Range<usize>,
+        pattern: &Pattern<T>,
+        mode: PlotMode,
+    ) -> Result<(), Error> {
+        self.check_rect(&x, &y)?;
+        self.plot_rect_with_pattern(x, y, pattern, mode);
+        Ok(())
+    }
+
+    pub fn try_replace_colour(
+        &mut self,
+        x: Range<usize>,
+        y: Range<usize>,
+        from: T,
+        to: T,
+    ) -> Result<(), Error> {
+        self.check_rect(&x, &y)?;
+        self.replace_colour(x, y, from, to);
+        Ok(())
+    }
+
+    pub fn try_blend_pixel<M: ColourMap<T>>(
+        &mut self,
+        x: usize,
+        y: usize,
+        colour: Colour,
+        blend: &Blend<M>,
+    ) -> Result<(), Error> {
+        self.check_point(x, y)?;
+        self.blend_pixel(x, y, colour, blend);
+        Ok(())
+    }
+
+    pub fn try_blend_range<M: ColourMap<T>>(
+        &mut self,
+        y: usize,
+        range: Range<usize>,
+        colour: Colour,
+        blend: &Blend<M>,
+    ) -> Result<(), Error> {
+        self.check_span(y, &range)?;
+        self.blend_range(y, range, colour, blend);
+        Ok(())
+    }
+
+    pub fn try_blend_rect_from<S, M>(
+        &mut self,
+        x: usize,
+        y: usize,
+        source: &FrameBuffer<S>,
+        source_x: Range<usize>,
+        source_y: Range<usize>,
+        blend: &Blend<M>,
+    ) -> Result<(), Error>
+    where
+        S: ColourPixel<PixelType = S>,
+        M: ColourMap<T>,
+    {
+        self.check_copy(x, y, source, &source_x, &source_y)?;
+        self.blend_rect_from(x, y, source, source_x, source_y, blend);
+        Ok(())
+    }
+
+    pub fn try_copy_rect_from(
+        &mut self,
+        x: usize,
+        y: usize,
+        source: &FrameBuffer<T>,
+        source_x: Range<usize>,
+        source_y: Range<usize>,
+    ) -> Result<(), Error> {
+        self.check_copy(x, y, source, &source_x, &source_y)?;
+        self.copy_rect_from(x, y, source, source_x, source_y);
+        Ok(())
+    }
+
+    pub fn try_copy_rect_from_keyed(
+        &mut self,
+        x: usize,
+        y: usize,
+        source: &FrameBuffer<T>,
+        source_x: Range<usize>,
+        source_y: Range<usize>,
+        key: T,
+    ) -> Result<(), Error> {
+        self.check_copy(x, y, source, &source_x, &source_y)?;
+        self.copy_rect_from_keyed(x, y, source, source_x, source_y, key);
+        Ok(())
+    }
+
+    pub fn try_combine_rect_from(
+        &mut self,
+        x: usize,
+        y: usize,
+        source: &FrameBuffer<T>,
+        source_x: Range<usize>,
+        source_y: Range<usize>,
+        mode: PlotMode,
+    ) -> Result<(), Error> {
+        self.check_copy(x, y, source, &source_x, &source_y)?;
+        self.combine_rect_from(x, y, source, source_x, source_y, mode);
+        Ok(())
+    }
+
+    pub fn try_copy_row_from(
+        &mut self,
+        x: usize,
+        y: usize,
+        source: &PixelRow<T>,
+        source_range: Range<usize>,
+    ) -> Result<(), Error> {
+        self.try_combine_row_from(x, y, source, source_range, PlotMode::Set)
+    }
+
+    pub fn try_combine_row_from(
+        &mut self,
+        x: usize,
+        y: usize,
+        source: &PixelRow<T>,
+        source_range: Range<usize>,
+        mode: PlotMode,
+    ) -> Result<(), Error> {
+        source.check_range(&source_range)?;
+        self.check_span(y, &(x..x.saturating_add(source_range.len())))?;
+        self.combine_row_from(x, y, source, source_range, mode);
+        Ok(())
+    }
+
+    // checks that `range` of row `y` lies within the framebuffer
+    fn check_span(&self, y: usize, range: &Range<usize>) -> Result<(), Error> {
+        self.check_rect(range, &(y..y.saturating_add(1)))
+    }
+
+    // checks that a rectangle lies within `source`, and that it fits in this framebuffer at `x`, `y`
+    fn check_copy<S: PixelChunk<PixelType = S>>(
+        &self,
+        x: usize,
+        y: usize,
+        source: &FrameBuffer<S>,
+        source_x: &Range<usize>,
+        source_y: &Range<usize>,
+    ) -> Result<(), Error> {
+        source.check_rect(source_x, source_y)?;
+        self.check_rect(
+            &(x..x.saturating_add(source_x.len())),
+            &(y..y.saturating_add(source_y.len())),
+        )
+    }
+}
+
+impl<T: PixelChunk<PixelType = T> + Eq + Hash> FrameBuffer<T> {
+    // count how many times each pixel value appears in a rectangle
+    pub fn histogram(&self, x: Range<usize>, y: Range<usize>) -> HashMap<T, usize> {
+        let mut counts = HashMap::new();
+        for row in self.rows_in(y) {
+            row.add_to_histogram(x.clone(), &mut counts);
+        }
+        counts
+    }
+
+    // the distinct pixel values used in a rectangle
+    pub fn colours(&self, x: Range<usize>, y: Range<usize>) -> HashSet<T> {
+        self.histogram(x, y).into_keys().collect()
+    }
+}
+
+impl<T: PixelChunk<PixelType = T>> FrameBuffer<T> {
+    // the smallest rectangle holding every pixel that isn't `background`, as `x` and `y` ranges
+    // or `None` if the whole framebuffer is `background`
+    pub fn bounding_box(&self, background: T) -> Option<(Range<usize>, Range<usize>)> {
+        let mut x: Option<Range<usize>> = None;
+        let mut y: Option<Range<usize>> = None;
+        for (index, row) in self.rows.iter().enumerate() {
+            let Some(bounds) = row.bounds(0..self.width, background) else {
+                continue;
+            };
+            x = Some(match x {
+                Some(x) => x.start.min(bounds.start)..x.end.max(bounds.end),
+                None => bounds,
+            });
+            y = Some(y.map_or(index, |y| y.start)..index + 1);
+        }
+        x.zip(y)
+    }
+
+    // whether every pixel in the framebuffer is `background`
+    pub fn is_blank(&self, background: T) -> bool {
+        self.rows
+            .iter()
+            .all(|row| row.bounds(0..self.width, background).is_none())
+    }
+}
+
+fn check_range(range: &Range<usize>, length: usize) -> Result<(), Error> {
+    if range.start > range.end || range.end > length {
+        Err(Error::InvalidRange {
+            start: range.start,
+            end: range.end,
+            length,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+// clips a range of rows or columns to `length`
+fn clip(range: Range<usize>, length: usize) -> Range<usize> {
+    range.start..range.end.min(length)
+}
+
+impl<T: ByteChunk<PixelType = T>> FrameBuffer<T> {
+    // remap every pixel in a rectangle through a lookup table
+    pub fn remap_rect(&mut self, x: Range<usize>, y: Range<usize>, lut: &ColourLut<T>) {
+        for row in clip(y, self.height) {
+            self.write_row(row, |row, write_mask| {
+                row.write_remap_range(x.clone(), lut, write_mask);
+            });
+        }
+    }
+
+    pub fn try_remap_rect(
+        &mut self,
+        x: Range<usize>,
+        y: Range<usize>,
+        lut: &ColourLut<T>,
+    ) -> Result<(), Error> {
+        self.check_rect(&x, &y)?;
+        self.remap_rect(x, y, lut);
+        Ok(())
+    }
+
+    // remap every pixel in the framebuffer through a lookup table
+    pub fn remap_all(&mut self, lut: &ColourLut<T>) {
+        self.remap_rect(0..self.width, 0..self.height, lut);
+    }
 }
 
 impl<T: PixelChunk> Index<usize> for FrameBuffer<T> {
@@ -58,10 +813,26 @@ impl<T: PixelChunk> Index<Range<usize>> for FrameBuffer<T> {
     }
 }
 
+impl<T: PixelChunk<PixelType = T>> PartialEq for FrameBuffer<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.width == other.width && self.height == other.height && self.rows == other.rows
+    }
+}
+
+impl<T: PixelChunk<PixelType = T> + Eq> Eq for FrameBuffer<T> {}
+
+impl<T: PixelChunk<PixelType = T> + Hash> Hash for FrameBuffer<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.width.hash(state);
+        self.rows.hash(state);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::pixel_formats::Pixel8;
+    use crate::colour::DirectColour;
+    use crate::pixel_formats::{Argb4444, Argb8888, Pixel4, Pixel8};
 
     #[test]
     fn can_create_frame_buffer() {
@@ -84,6 +855,112 @@ mod tests {
         }
     }
 
+    #[test]
+    fn can_scroll_frame_buffer() {
+        let mut buffer: FrameBuffer<Pixel4> = FrameBuffer::new_scrollable(4, 2, 10);
+        assert_eq!(buffer.width(), 4);
+        assert_eq!(buffer.max_scroll_x(), 6);
+        buffer.set_scroll_x(6);
+        buffer.fill_range(1, 3..4, 7.into());
+        buffer.scroll_x_by(-3);
+        assert_eq!(buffer.scroll_x(), 3);
+        assert_eq!(buffer.pixel(3, 1), Some(0.into()));
+        assert_eq!(buffer.row(1).unwrap().width(), 4);
+        buffer.scroll_x_by(20);
+        assert_eq!(buffer.pixel(3, 1), Some(7.into()));
+        assert_eq!(buffer.pixel(3, 0), Some(0.into()));
+    }
+
+    #[test]
+    fn can_compare_frame_buffers() {
+        let mut buffer: FrameBuffer<Pixel4> = FrameBuffer::new_scrollable(3, 2, 8);
+        buffer.set_scroll_x(3);
+        buffer.fill_range(1, 0..3, 5.into());
+        let mut other: FrameBuffer<Pixel4> = FrameBuffer::new(3, 2);
+        assert!(buffer != other);
+        other.fill_range(1, 0..3, 5.into());
+        other.set_write_mask(3.into());
+        assert!(buffer == other);
+        buffer.set_scroll_x(0);
+        assert!(buffer != other);
+        assert!(other != FrameBuffer::new(3, 3));
+    }
+
+    #[test]
+    fn clones_share_unchanged_rows() {
+        let mut buffer: FrameBuffer<Pixel8> = FrameBuffer::new_with(4, 3, 1.into());
+        let snapshot = buffer.clone();
+        buffer.fill_range(1, 0..2, 2.into());
+        assert!(buffer.rows[0].shares_storage_with(&snapshot.rows[0]));
+        assert!(!buffer.rows[1].shares_storage_with(&snapshot.rows[1]));
+        assert!(buffer.rows[2].shares_storage_with(&snapshot.rows[2]));
+        assert_eq!(snapshot.pixel(0, 1), Some(1.into()));
+        assert_eq!(buffer.pixel(0, 1), Some(2.into()));
+    }
+
+    #[test]
+    fn clipped_writes_keep_rows_shared() {
+        let mut buffer: FrameBuffer<Pixel8> = FrameBuffer::new_with(4, 2, 1.into());
+        let mut clip_mask: FrameBuffer<Pixel1> = FrameBuffer::new(4, 2);
+        clip_mask.fill_range(1, 0..2, 1.into());
+        buffer.set_clip_mask(Some(clip_mask));
+        let snapshot = buffer.clone();
+        buffer.fill_range(0, 0..4, 2.into());
+        buffer.fill_range(1, 2..4, 2.into());
+        buffer.set_pixel(3, 1, 2.into());
+        assert!(buffer.rows[0].shares_storage_with(&snapshot.rows[0]));
+        assert!(buffer.rows[1].shares_storage_with(&snapshot.rows[1]));
+        buffer.fill_range(1, 0..4, 2.into());
+        assert!(!buffer.rows[1].shares_storage_with(&snapshot.rows[1]));
+        assert_eq!(buffer.pixel(1, 1), Some(2.into()));
+        assert_eq!(buffer.pixel(2, 1), Some(1.into()));
+    }
+
+    #[test]
+    fn can_resize_frame_buffer() {
+        let mut buffer: FrameBuffer<Pixel4> = FrameBuffer::new(3, 2);
+        buffer.set_pixel(0, 0, 1.into());
+        buffer.set_pixel(2, 1, 2.into());
+        buffer.resize(5, 4, Anchor::BottomRight, 7.into());
+        assert_eq!(buffer.width(), 5);
+        assert_eq!(buffer.height(), 4);
+        assert_eq!(buffer.pixel(2, 2), Some(1.into()));
+        assert_eq!(buffer.pixel(4, 3), Some(2.into()));
+        assert_eq!(buffer.pixel(1, 2), Some(7.into()));
+        assert_eq!(buffer.pixel(4, 1), Some(7.into()));
+        assert_eq!(buffer.row(3).unwrap().width(), 5);
+
+        // an odd row or column is removed from the end
+        buffer.resize(3, 3, Anchor::Centre, 0.into());
+        assert_eq!(buffer.pixel(1, 2), Some(1.into()));
+        assert_eq!(buffer.pixel(2, 2), Some(0.into()));
+        buffer.resize(2, 2, Anchor::BottomLeft, 0.into());
+        assert_eq!(buffer.pixel(1, 1), Some(1.into()));
+        assert_eq!(buffer.pixel(0, 0), Some(7.into()));
+        assert!(buffer.row(2).is_none());
+    }
+
+    #[test]
+    fn can_analyse_frame_buffer() {
+        let mut buffer: FrameBuffer<Pixel4> = FrameBuffer::new_with(6, 5, 0x11.into());
+        assert!(buffer.is_blank(1.into()));
+        assert_eq!(buffer.bounding_box(1.into()), None);
+        buffer.fill_range(1, 2..4, 3.into());
+        buffer.set_pixel(4, 3, 5.into());
+        assert!(!buffer.is_blank(1.into()));
+        assert_eq!(buffer.bounding_box(1.into()), Some((2..5, 1..4)));
+
+        let histogram = buffer.histogram(0..6, 0..5);
+        assert_eq!(histogram[&1.into()], 27);
+        assert_eq!(histogram[&3.into()], 2);
+        assert_eq!(histogram[&5.into()], 1);
+        assert_eq!(buffer.histogram(3..10, 1..2)[&1.into()], 2);
+        assert_eq!(
+            buffer.colours(0..3, 0..2),
+            HashSet::from([1.into(), 3.into()])
+        );
+    }
+
     #[test]
     fn can_get_row() {
         let buffer: FrameBuffer<Pixel8> = FrameBuffer::new(3, 3);
@@ -93,6 +970,328 @@ mod tests {
         assert_eq!(buffer.row(3), None);
     }
 
+    #[test]
+    fn can_copy_between_rows() {
+        let mut buffer: FrameBuffer<Pixel8> = FrameBuffer::new(3, 3);
+        let source = PixelRow::from_vec(vec![1.into(), 2.into(), 3.into()]);
+        buffer.copy_row_from(1, 1, &source, 0..3);
+        assert_eq!(buffer.pixel(0, 1), Some(0.into()));
+        assert_eq!(buffer.pixel(1, 1), Some(1.into()));
+        assert_eq!(buffer.pixel(2, 1), Some(2.into()));
+        // rows that don't exist are ignored
+        buffer.copy_row_from(0, 3, &source, 0..3);
+
+        // the write mask and clip mask apply
+        buffer.set_write_mask(0x1.into());
+        buffer.combine_row_from(0, 2, &source, 0..3, PlotMode::Or);
+        assert_eq!(buffer.pixel(0, 2), Some(1.into()));
+        assert_eq!(buffer.pixel(1, 2), Some(0.into()));
+        let mut clip_mask: FrameBuffer<Pixel1> = FrameBuffer::new(3, 3);
+        clip_mask.set_pixel(2, 0, 1.into());
+        buffer.set_clip_mask(Some(clip_mask));
+        buffer.set_write_mask(0xFF.into());
+        buffer.copy_row_from(0, 0, &source, 0..3);
+        assert_eq!(buffer.pixel(0, 0), Some(0.into()));
+        assert_eq!(buffer.pixel(2, 0), Some(3.into()));
+    }
+
+    #[test]
+    fn can_set_pixels() {
+        let mut buffer: FrameBuffer<Pixel4> = FrameBuffer::new(5, 2);
+        buffer.set_pixel(1, 0, 7.into());
+        buffer.fill_range(1, 1..4, 9.into());
+        buffer.plot_range(1, 0..5, 1.into(), PlotMode::Xor);
+        assert_eq!(buffer.pixel(1, 0), Some(7.into()));
+        assert_eq!(buffer.pixel(0, 1), Some(1.into()));
+        assert_eq!(buffer.pixel(2, 1), Some(8.into()));
+        assert_eq!(buffer.pixel(4, 1), Some(1.into()));
+        // writes outside of the buffer are ignored
+        buffer.set_pixel(0, 2, 1.into());
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn clips_or_rejects_out_of_bounds_writes() {
+        let mut buffer: FrameBuffer<Pixel4> = FrameBuffer::new(5, 3);
+        buffer.set_pixel(5, 0, 1.into());
+        buffer.set_pixel(0, 3, 1.into());
+        buffer.fill_range(1, 3..100, 2.into());
+        buffer.fill_range(2, 4..1, 2.into());
+        buffer.replace_colour(0..usize::MAX, 0..usize::MAX, 2.into(), 3.into());
+        assert_eq!(buffer.pixel(4, 1), Some(3.into()));
+        assert_eq!(buffer.pixel(2, 1), Some(0.into()));
+        assert_eq!(buffer.pixel(5, 0), None);
+
+        assert_eq!(
+            buffer.try_set_pixel(5, 0, 1.into()),
+            Err(Error::OutOfBounds {
+                index: 5,
+                length: 5
+            })
+        );
+        assert_eq!(
+            buffer.try_set_pixel(0, 3, 1.into()),
+            Err(Error::OutOfBounds {
+                index: 3,
+                length: 3
+            })
+        );
+        assert_eq!(
+            buffer.try_fill_range(0, 2..6, 1.into()),
+            Err(Error::InvalidRange {
+                start: 2,
+                end: 6,
+                length: 5
+            })
+        );
+        assert_eq!(buffer.pixel(2, 0), Some(0.into()));
+        assert_eq!(buffer.try_fill_range(0, 2..5, 1.into()), Ok(()));
+        assert_eq!(buffer.pixel(4, 0), Some(1.into()));
+
+        let source = buffer.clone();
+        assert!(buffer
+            .try_copy_rect_from(3, 0, &source, 0..3, 0..2)
+            .is_err());
+        assert_eq!(buffer.try_copy_rect_from(2, 1, &source, 0..3, 0..2), Ok(()));
+        assert_eq!(buffer.pixel(4, 1), Some(1.into()));
+    }
+
+    #[test]
+    fn checked_variants_leave_buffer_untouched() {
+        let mut buffer: FrameBuffer<Pixel4> = FrameBuffer::new_with(4, 3, 0x12.into());
+        let before = buffer.clone();
+        let source: FrameBuffer<Pixel4> = FrameBuffer::new_with(2, 2, 0x33.into());
+        let pattern = Pattern::solid(5.into());
+        let mut line = LinePattern::new(0b1010, 4);
+        assert!(buffer
+            .try_plot_range_dotted(3, 0..2, 9.into(), PlotMode::Set, &mut line)
+            .is_err());
+        assert_eq!(line.phase(), 0);
+        assert!(buffer
+            .try_fill_rect_with_pattern(0..5, 0..3, &pattern)
+            .is_err());
+        assert!(buffer
+            .try_plot_range_with_pattern(0, 2..5, &pattern, PlotMode::Xor)
+            .is_err());
+        assert!(buffer
+            .try_replace_colour(0..4, 2..4, 1.into(), 0.into())
+            .is_err());
+        assert!(buffer
+            .try_copy_rect_from_keyed(3, 0, &source, 0..2, 0..2, 0.into())
+            .is_err());
+        assert!(buffer
+            .try_combine_rect_from(0, 0, &source, 1..3, 0..2, PlotMode::Or)
+            .is_err());
+        assert!(buffer
+            .try_copy_row_from(2, 0, source.row(0).unwrap(), 0..3)
+            .is_err());
+        assert!(buffer == before);
+
+        assert_eq!(
+            buffer.try_combine_rect_from(2, 1, &source, 0..2, 0..2, PlotMode::Or),
+            Ok(())
+        );
+        assert_eq!(buffer.pixel(3, 2), Some(3.into()));
+        assert_eq!(buffer.pixel(1, 2), before.pixel(1, 2));
+    }
+
+    #[test]
+    fn can_set_pixels_with_write_mask() {
+        let mut buffer: FrameBuffer<Pixel4> = FrameBuffer::new_with(4, 2, 0x33.into());
+        buffer.set_write_mask(0x8.into());
+        assert_eq!(buffer.write_mask(), 0x8.into());
+        buffer.set_pixel(0, 0, 0xF.into());
+        buffer.fill_range(1, 1..4, 0xF.into());
+        assert_eq!(buffer.pixel(0, 0), Some(0xB.into()));
+        assert_eq!(buffer.pixel(1, 0), Some(0x3.into()));
+        assert_eq!(buffer.pixel(0, 1), Some(0x3.into()));
+        assert_eq!(buffer.pixel(1, 1), Some(0xB.into()));
+        assert_eq!(buffer.pixel(3, 1), Some(0xB.into()));
+    }
+
+    #[test]
+    fn can_copy_rect() {
+        let mut source: FrameBuffer<Pixel4> = FrameBuffer::new(4, 4);
+        for y in 0..4 {
+            for x in 0..4 {
+                source.set_pixel(x, y, ((y * 4 + x) as u8).into());
+            }
+        }
+        let mut dest: FrameBuffer<Pixel4> = FrameBuffer::new_with(5, 3, 0xFF.into());
+        dest.copy_rect_from(2, 1, &source, 1..4, 1..4);
+        for y in 0..3 {
+            for x in 0..5 {
+                let expected = if x >= 2 && y >= 1 {
+                    (y * 4 + x - 1) as u8
+                } else {
+                    0xF
+                };
+                assert_eq!(dest.pixel(x, y), Some(expected.into()));
+            }
+        }
+    }
+
+    #[test]
+    fn can_copy_rect_with_colour_key() {
+        let mut source: FrameBuffer<Pixel8> = FrameBuffer::new(3, 2);
+        source.set_pixel(1, 0, 4.into());
+        source.set_pixel(2, 1, 5.into());
+        let mut dest: FrameBuffer<Pixel8> = FrameBuffer::new_with(3, 2, 9.into());
+        dest.copy_rect_from_keyed(0, 0, &source, 0..3, 0..2, 0.into());
+        assert_eq!(dest.pixel(0, 0), Some(9.into()));
+        assert_eq!(dest.pixel(1, 0), Some(4.into()));
+        assert_eq!(dest.pixel(2, 0), Some(9.into()));
+        assert_eq!(dest.pixel(2, 1), Some(5.into()));
+
+        dest.combine_rect_from(0, 0, &source, 0..3, 0..2, PlotMode::Or);
+        assert_eq!(dest.pixel(0, 0), Some(9.into()));
+        assert_eq!(dest.pixel(1, 0), Some(4.into()));
+        assert_eq!(dest.pixel(2, 1), Some(5.into()));
+    }
+
+    #[test]
+    fn can_blend_rect() {
+        let mut source: FrameBuffer<Argb4444> = FrameBuffer::new(2, 2);
+        source.set_pixel(0, 0, 0xFFFF.into());
+        source.set_pixel(1, 1, 0x8FFF.into());
+        let mut dest: FrameBuffer<Argb8888> = FrameBuffer::new_with(3, 3, 0xFF000000.into());
+        dest.blend_rect_from(1, 1, &source, 0..2, 0..2, &Blend::new(DirectColour));
+        assert_eq!(dest.pixel(0, 0), Some(0xFF000000.into()));
+        assert_eq!(dest.pixel(1, 1), Some(0xFFFFFFFF.into()));
+        assert_eq!(dest.pixel(2, 1), Some(0xFF000000.into()));
+        assert_eq!(dest.pixel(2, 2), Some(0xFF888888.into()));
+
+        let blend = Blend::new(DirectColour).with_global_alpha(0);
+        dest.blend_range(0, 0..3, Colour::opaque(255, 255, 255), &blend);
+        assert_eq!(dest.pixel(0, 0), Some(0xFF000000.into()));
+        dest.blend_pixel(
+            0,
+            0,
+            Colour::opaque(255, 255, 255),
+            &Blend::new(DirectColour),
+        );
+        assert_eq!(dest.pixel(0, 0), Some(0xFFFFFFFF.into()));
+    }
+
+    #[test]
+    fn can_clip_writes_with_mask() {
+        let mut mask: FrameBuffer<Pixel1> = FrameBuffer::new(6, 2);
+        mask.fill_range(0, 1..4, 1.into());
+        mask.set_pixel(5, 1, 1.into());
+
+        let mut buffer: FrameBuffer<Pixel4> = FrameBuffer::new(6, 3);
+        buffer.set_clip_mask(Some(mask));
+        assert!(buffer.clip_mask().is_some());
+        buffer.fill_range(0, 0..6, 5.into());
+        buffer.set_pixel(0, 1, 5.into());
+        buffer.set_pixel(5, 1, 5.into());
+        // row 2 is outside of the mask, so is clipped entirely
+        buffer.fill_range(2, 0..6, 5.into());
+
+        let source: FrameBuffer<Pixel4> = FrameBuffer::new_with(6, 3, 0x99.into());
+        buffer.copy_rect_from(0, 1, &source, 0..6, 0..2);
+        for x in 0..6 {
+            let expected = if (1..4).contains(&x) { 5 } else { 0 };
+            assert_eq!(buffer.pixel(x, 0), Some(expected.into()));
+            let expected = if x == 5 { 9 } else { 0 };
+            assert_eq!(buffer.pixel(x, 1), Some(expected.into()));
+            assert_eq!(buffer.pixel(x, 2), Some(0.into()));
+        }
+
+        buffer.clip_mask_mut().unwrap().set_pixel(0, 1, 1.into());
+        buffer.set_pixel(0, 1, 3.into());
+        assert_eq!(buffer.pixel(0, 1), Some(3.into()));
+
+        buffer.set_clip_mask(None);
+        buffer.fill_range(2, 0..6, 5.into());
+        assert_eq!(buffer.pixel(0, 2), Some(5.into()));
+    }
+
+    #[test]
+    fn can_fill_rect_with_pattern() {
+        // a 2x2 checkerboard
+        let pattern: Pattern<Pixel4> =
+            Pattern::new(2, 2, &[1.into(), 0.into(), 0.into(), 1.into()]);
+        let mut buffer: FrameBuffer<Pixel4> = FrameBuffer::new_with(7, 4, 0x22.into());
+        buffer.fill_rect_with_pattern(1..6, 1..5, &pattern);
+        for y in 0..4 {
+            for x in 0..7 {
+                let expected = if (1..6).contains(&x) && y >= 1 {
+                    ((x + y + 1) % 2) as u8
+                } else {
+                    2
+                };
+                assert_eq!(buffer.pixel(x, y), Some(expected.into()));
+            }
+        }
+
+        buffer.plot_rect_with_pattern(0..7, 0..1, &pattern, PlotMode::Or);
+        assert_eq!(buffer.pixel(0, 0), Some(3.into()));
+        assert_eq!(buffer.pixel(1, 0), Some(2.into()));
+    }
+
+    #[test]
+    fn can_plot_dotted_lines() {
+        let mut pattern = LinePattern::new(0xAAAA_AAAA_AAAA_AAAA, 64);
+        let mut buffer: FrameBuffer<Pixel4> = FrameBuffer::new(6, 2);
+        buffer.plot_range_dotted(0, 0..3, 1.into(), PlotMode::Set, &mut pattern);
+        // off screen rows still move the pattern on
+        buffer.plot_range_dotted(5, 0..3, 1.into(), PlotMode::Set, &mut pattern);
+        buffer.plot_pixel_dotted(0, 1, 1.into(), PlotMode::Set, &mut pattern);
+        buffer.plot_pixel_dotted(1, 1, 1.into(), PlotMode::Set, &mut pattern);
+        assert_eq!(buffer.pixel(0, 0), Some(1.into()));
+        assert_eq!(buffer.pixel(1, 0), Some(0.into()));
+        assert_eq!(buffer.pixel(2, 0), Some(1.into()));
+        assert_eq!(buffer.pixel(0, 1), Some(1.into()));
+        assert_eq!(buffer.pixel(1, 1), Some(0.into()));
+        assert_eq!(pattern.phase(), 8);
+    }
+
+    #[test]
+    fn dotted_lines_continue_past_right_edge() {
+        let mut pattern = LinePattern::new(0b110 << 61, 3);
+        let mut buffer: FrameBuffer<Pixel4> = FrameBuffer::new(4, 2);
+        buffer.plot_range_dotted(0, 2..9, 1.into(), PlotMode::Set, &mut pattern);
+        assert_eq!(pattern.phase(), 1);
+        assert_eq!(buffer.pixel(2, 0), Some(1.into()));
+        assert_eq!(buffer.pixel(3, 0), Some(1.into()));
+        // the next segment carries on from the pixel after the end of the last one
+        buffer.plot_range_dotted(1, 0..3, 1.into(), PlotMode::Set, &mut pattern);
+        assert_eq!(buffer.pixel(0, 1), Some(1.into()));
+        assert_eq!(buffer.pixel(1, 1), Some(0.into()));
+        assert_eq!(buffer.pixel(2, 1), Some(1.into()));
+        assert_eq!(pattern.phase(), 1);
+
+        let mut row: PixelRow<Pixel4> = PixelRow::new(4);
+        pattern.restart();
+        row.plot_range_dotted(6..8, 1.into(), PlotMode::Set, &mut pattern);
+        assert_eq!(pattern.phase(), 2);
+    }
+
+    #[test]
+    fn can_replace_colours() {
+        let mut buffer: FrameBuffer<Pixel4> = FrameBuffer::new_with(5, 3, 0x11.into());
+        buffer.set_pixel(2, 1, 2.into());
+        buffer.replace_colour(1..4, 1..3, 1.into(), 7.into());
+        assert_eq!(buffer.pixel(0, 1), Some(1.into()));
+        assert_eq!(buffer.pixel(1, 1), Some(7.into()));
+        assert_eq!(buffer.pixel(2, 1), Some(2.into()));
+        assert_eq!(buffer.pixel(3, 2), Some(7.into()));
+        assert_eq!(buffer.pixel(4, 2), Some(1.into()));
+        assert_eq!(buffer.pixel(1, 0), Some(1.into()));
+
+        buffer.replace_colour_all(7.into(), 2.into());
+        let lut = ColourLut::replacing(2.into(), 9.into());
+        buffer.remap_all(&lut);
+        assert_eq!(buffer.pixel(1, 1), Some(9.into()));
+        assert_eq!(buffer.pixel(2, 1), Some(9.into()));
+        assert_eq!(buffer.pixel(0, 0), Some(1.into()));
+
+        buffer.remap_rect(0..1, 0..1, &ColourLut::from_table(&[0.into(), 3.into()]));
+        assert_eq!(buffer.pixel(0, 0), Some(3.into()));
+        assert_eq!(buffer.pixel(1, 0), Some(1.into()));
+    }
+
     #[test]
     fn can_get_pixel() {
         let buffer: FrameBuffer<Pixel8> = FrameBuffer::new(3, 3);
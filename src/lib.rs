@@ -1,3 +1,9 @@
+pub mod colour;
+pub mod error;
 pub mod frame_buffer;
+pub mod pattern;
 pub mod pixel_formats;
 pub mod pixel_row;
+pub mod plot_mode;
+pub mod rle;
+pub mod row_slice;
@@ -0,0 +1,204 @@
+use crate::pixel_formats::PixelChunk;
+
+// A repeating pattern of pixels, such as an Acorn extended colour fill (ECF)
+// Patterns tile from the origin of the row or framebuffer they're drawn into
+// Each pattern row is pre-expanded to whole chunks, repeating the row as needed
+// so that it ends on a chunk boundary, allowing fills to work chunk-at-a-time
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pattern<T: PixelChunk> {
+    width: usize,
+    height: usize,
+    rows: Vec<Vec<T>>,
+}
+
+impl<T: PixelChunk<PixelType = T>> Pattern<T> {
+    // create a pattern from `pixels`, given in rows, with each entry being a single pixel value
+    // missing pixels are treated as zero
+    pub fn new(width: usize, height: usize, pixels: &[T]) -> Pattern<T> {
+        let width = width.max(1);
+        let height = height.max(1);
+        // length of an expanded row, which is a multiple of both the pattern width and chunk size
+        let expanded = lcm(width, T::pixels());
+        let rows = (0..height)
+            .map(|y| {
+                let mut chunks = vec![T::default(); expanded / T::pixels()];
+                for x in 0..expanded {
+                    let pixel = pixels
+                        .get(y * width + x % width)
+                        .copied()
+                        .unwrap_or_default();
+                    chunks[x / T::pixels()].set_pixel(x % T::pixels(), pixel);
+                }
+                chunks
+            })
+            .collect();
+        Pattern {
+            width,
+            height,
+            rows,
+        }
+    }
+
+    // create a pattern of a single colour
+    pub fn solid(pixel: T) -> Pattern<T> {
+        Pattern::new(1, 1, &[pixel])
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // the pattern pixel at absolute coordinates `x`, `y`
+    pub fn pixel(&self, x: usize, y: usize) -> T {
+        let row = &self.rows[y % self.height];
+        let x = x % self.width;
+        row[x / T::pixels()].get_pixel(x % T::pixels()).unwrap()
+    }
+
+    // gets a chunk of pattern pixels starting at absolute coordinates `x`, `y`
+    pub fn chunk_at(&self, x: isize, y: usize) -> T {
+        let row = &self.rows[y % self.height];
+        let offset = x.rem_euclid((row.len() * T::pixels()) as isize) as usize;
+        let chunk_index = offset / T::pixels();
+        let shift = offset % T::pixels();
+        if shift == 0 {
+            row[chunk_index]
+        } else {
+            row[chunk_index].shift_left(shift)
+                | row[(chunk_index + 1) % row.len()].shift_right(T::pixels() - shift)
+        }
+    }
+}
+
+// An on/off pattern for dotted lines, as used by Acorn's dotted line PLOT modes
+// Up to 64 pixels long, with the first pixel of the pattern in the highest bit
+// The current phase is kept between uses, so consecutive line segments continue the pattern
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LinePattern {
+    bits: u64,
+    length: u32,
+    phase: u32,
+}
+
+impl LinePattern {
+    // `length` is the number of bits of the pattern to use, from 1 to 64
+    pub fn new(bits: u64, length: u32) -> LinePattern {
+        LinePattern {
+            bits,
+            length: length.clamp(1, 64),
+            phase: 0,
+        }
+    }
+
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    // position in the pattern of the next pixel
+    pub fn phase(&self) -> u32 {
+        self.phase
+    }
+
+    pub fn set_phase(&mut self, phase: u32) {
+        self.phase = phase % self.length;
+    }
+
+    // start the pattern again from its first pixel
+    pub fn restart(&mut self) {
+        self.phase = 0;
+    }
+
+    // move on by `count` pixels without drawing
+    pub fn skip(&mut self, count: usize) {
+        self.phase = ((self.phase as usize + count) % self.length as usize) as u32;
+    }
+
+    // whether the next pixel is on, moving the pattern on by one pixel
+    pub fn next_pixel(&mut self) -> bool {
+        let on = self.bits & (1 << (63 - self.phase)) != 0;
+        self.skip(1);
+        on
+    }
+
+    // flags for the next `count` pixels (up to 8) in the highest bits of a byte
+    // moving the pattern on by `count` pixels
+    pub(crate) fn next_flags(&mut self, count: usize) -> u8 {
+        let mut flags = 0;
+        for i in 0..count.min(8) {
+            if self.next_pixel() {
+                flags |= 0x80 >> i;
+            }
+        }
+        flags
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn lcm(a: usize, b: usize) -> usize {
+    a / gcd(a, b) * b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel_formats::{Pixel1, Pixel4};
+
+    #[test]
+    fn can_create_pattern() {
+        let pattern: Pattern<Pixel4> =
+            Pattern::new(3, 2, &[1.into(), 2.into(), 3.into(), 4.into(), 5.into()]);
+        assert_eq!(pattern.width(), 3);
+        assert_eq!(pattern.height(), 2);
+        assert_eq!(pattern.pixel(0, 0), 1.into());
+        assert_eq!(pattern.pixel(5, 0), 3.into());
+        assert_eq!(pattern.pixel(4, 3), 5.into());
+        // missing pixels are zero
+        assert_eq!(pattern.pixel(2, 1), 0.into());
+    }
+
+    #[test]
+    fn can_step_through_line_pattern() {
+        let mut pattern = LinePattern::new(0b101 << 61, 4);
+        let pixels: Vec<bool> = (0..6).map(|_| pattern.next_pixel()).collect();
+        assert_eq!(pixels, [true, false, true, false, true, false]);
+        assert_eq!(pattern.phase(), 2);
+
+        pattern.restart();
+        assert_eq!(pattern.phase(), 0);
+        assert_eq!(pattern.next_flags(5), 0b1010_1000);
+        pattern.skip(6);
+        assert_eq!(pattern.phase(), 3);
+
+        let mut pattern = LinePattern::new(1, 100);
+        assert_eq!(pattern.length(), 64);
+        pattern.set_phase(63);
+        assert!(pattern.next_pixel());
+        assert_eq!(pattern.phase(), 0);
+    }
+
+    #[test]
+    fn can_get_pattern_chunks() {
+        let pattern: Pattern<Pixel4> = Pattern::new(3, 1, &[1.into(), 2.into(), 3.into()]);
+        assert_eq!(pattern.chunk_at(0, 0).value, 0x12);
+        assert_eq!(pattern.chunk_at(1, 0).value, 0x23);
+        assert_eq!(pattern.chunk_at(2, 0).value, 0x31);
+        assert_eq!(pattern.chunk_at(5, 0).value, 0x31);
+        assert_eq!(pattern.chunk_at(-1, 0).value, 0x31);
+
+        let pattern: Pattern<Pixel1> = Pattern::new(3, 1, &[1.into(), 0.into(), 0.into()]);
+        assert_eq!(pattern.chunk_at(0, 0).value, 0b1001_0010);
+        assert_eq!(pattern.chunk_at(1, 0).value, 0b0010_0100);
+        assert_eq!(pattern.chunk_at(8, 0).value, 0b0100_1001);
+    }
+}
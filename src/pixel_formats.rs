@@ -1,10 +1,75 @@
 // use std::ops::Index;
-
-pub trait PixelChunk: Copy + Clone + Default + IntoIterator<Item = Self> {
+use std::ops::{BitAnd, BitOr, BitXor, Not, Range};
+
+pub trait PixelChunk:
+    Copy
+    + Clone
+    + Default
+    + PartialEq
+    + IntoIterator<Item = Self>
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+{
     type PixelType: Clone;
 
     fn pixels() -> usize;
 
+    // a chunk with all the bits belonging to the pixel at `index` set
+    fn pixel_mask(index: usize) -> Self;
+
+    // move pixels towards the start of the chunk by `count` pixels, filling the end with zeros
+    fn shift_left(self, count: usize) -> Self;
+
+    // move pixels towards the end of the chunk by `count` pixels, filling the start with zeros
+    fn shift_right(self, count: usize) -> Self;
+
+    // the pixels of the chunk in reverse order
+    fn reverse_pixels(self) -> Self;
+
+    // a chunk with all the bits belonging to the pixels in `range` set
+    fn range_mask(range: Range<usize>) -> Self {
+        let mut mask = Self::default();
+        for i in range {
+            mask = mask | Self::pixel_mask(i);
+        }
+        mask
+    }
+
+    // a mask chunk with all the bits set for each pixel whose matching flag in `flags` is set
+    // flags are taken from the start of the `Pixel1` chunk, so chunks must hold at most 8 pixels
+    fn mask_from_flags(flags: Pixel1) -> Self {
+        let mut mask = Self::default();
+        for i in 0..Self::pixels() {
+            if flags.value & (0x80 >> i) != 0 {
+                mask = mask | Self::pixel_mask(i);
+            }
+        }
+        mask
+    }
+
+    // the bits of each pixel that are compared when matching a colour key
+    // formats with an alpha channel leave it out, so a key matches a colour whatever its alpha
+    fn key_bits() -> Self {
+        !Self::default()
+    }
+
+    // a chunk with all the bits set for each pixel that matches `pixel`
+    fn match_mask(self, pixel: Self::PixelType) -> Self
+    where
+        Self: Sized,
+    {
+        let key = Self::filled_pixel(pixel);
+        let mut mask = Self::default();
+        for i in 0..Self::pixels() {
+            if self.get_pixel(i) == key.get_pixel(i) {
+                mask = mask | Self::pixel_mask(i);
+            }
+        }
+        mask
+    }
+
     fn get_pixel(&self, index: usize) -> Option<Self>
     where
         Self: Sized; // Use the associated type for the return type.
@@ -30,6 +95,155 @@ pub trait PixelChunk: Copy + Clone + Default + IntoIterator<Item = Self> {
     }
 }
 
+// bitwise operators for chunks that store their pixels in a `value` field
+macro_rules! impl_bit_ops {
+    ($chunk:ident) => {
+        impl BitAnd for $chunk {
+            type Output = Self;
+
+            #[inline]
+            fn bitand(self, rhs: Self) -> Self {
+                $chunk {
+                    value: self.value & rhs.value,
+                }
+            }
+        }
+
+        impl BitOr for $chunk {
+            type Output = Self;
+
+            #[inline]
+            fn bitor(self, rhs: Self) -> Self {
+                $chunk {
+                    value: self.value | rhs.value,
+                }
+            }
+        }
+
+        impl BitXor for $chunk {
+            type Output = Self;
+
+            #[inline]
+            fn bitxor(self, rhs: Self) -> Self {
+                $chunk {
+                    value: self.value ^ rhs.value,
+                }
+            }
+        }
+
+        impl Not for $chunk {
+            type Output = Self;
+
+            #[inline]
+            fn not(self) -> Self {
+                $chunk { value: !self.value }
+            }
+        }
+    };
+}
+
+// implements a chunk that holds a single pixel in a `value` field
+// used for multi-byte, direct colour formats
+// `$colour_bits` are the bits holding the colour, without any alpha
+macro_rules! single_pixel_chunk {
+    ($chunk:ident, $value:ty, $colour_bits:expr) => {
+        #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+        pub struct $chunk {
+            pub value: $value,
+        }
+
+        impl PixelChunk for $chunk {
+            type PixelType = $chunk;
+
+            #[inline]
+            fn pixels() -> usize {
+                1
+            }
+
+            fn get_pixel(&self, index: usize) -> Option<Self> {
+                match index {
+                    0 => Some(*self),
+                    _ => None,
+                }
+            }
+
+            fn set_pixel(&mut self, _index: usize, pixel: Self::PixelType) {
+                *self = pixel;
+            }
+
+            #[inline]
+            fn pixel_mask(index: usize) -> Self {
+                match index {
+                    0 => $chunk { value: !0 },
+                    _ => $chunk { value: 0 },
+                }
+            }
+
+            #[inline]
+            fn key_bits() -> Self {
+                $chunk {
+                    value: $colour_bits,
+                }
+            }
+
+            #[inline]
+            fn match_mask(self, pixel: Self::PixelType) -> Self {
+                if self == pixel {
+                    $chunk { value: !0 }
+                } else {
+                    $chunk { value: 0 }
+                }
+            }
+
+            #[inline]
+            fn shift_left(self, count: usize) -> Self {
+                match count {
+                    0 => self,
+                    _ => $chunk { value: 0 },
+                }
+            }
+
+            #[inline]
+            fn shift_right(self, count: usize) -> Self {
+                self.shift_left(count)
+            }
+
+            #[inline]
+            fn reverse_pixels(self) -> Self {
+                self
+            }
+        }
+
+        impl_bit_ops!($chunk);
+
+        impl IntoIterator for $chunk {
+            type Item = $chunk;
+            type IntoIter = PixelChunkIterator<Self>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                PixelChunkIterator {
+                    chunk: self,
+                    index: 0,
+                }
+            }
+        }
+
+        impl From<$value> for $chunk {
+            #[inline]
+            fn from(value: $value) -> Self {
+                $chunk { value }
+            }
+        }
+
+        impl From<$chunk> for $value {
+            #[inline]
+            fn from(pixel: $chunk) -> $value {
+                pixel.value
+            }
+        }
+    };
+}
+
 pub struct PixelChunkIterator<T: PixelChunk> {
     chunk: T,
     index: usize,
@@ -45,7 +259,7 @@ impl<T: PixelChunk> Iterator for PixelChunkIterator<T> {
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy, Default)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
 pub struct Pixel8 {
     pub value: u8,
 }
@@ -58,6 +272,50 @@ impl PixelChunk for Pixel8 {
         1
     }
 
+    #[inline]
+    fn pixel_mask(index: usize) -> Self {
+        match index {
+            0 => Pixel8 { value: 0xFF },
+            _ => Pixel8 { value: 0 },
+        }
+    }
+
+    #[inline]
+    fn match_mask(self, pixel: Self::PixelType) -> Self {
+        if self == pixel {
+            Pixel8 { value: 0xFF }
+        } else {
+            Pixel8 { value: 0 }
+        }
+    }
+
+    #[inline]
+    fn shift_left(self, count: usize) -> Self {
+        match count {
+            0 => self,
+            _ => Pixel8 { value: 0 },
+        }
+    }
+
+    #[inline]
+    fn shift_right(self, count: usize) -> Self {
+        self.shift_left(count)
+    }
+
+    #[inline]
+    fn reverse_pixels(self) -> Self {
+        self
+    }
+
+    #[inline]
+    fn range_mask(range: Range<usize>) -> Self {
+        if range.contains(&0) {
+            Pixel8 { value: 0xFF }
+        } else {
+            Pixel8 { value: 0 }
+        }
+    }
+
     fn get_pixel(&self, index: usize) -> Option<Self> {
         match index {
             0 => Some(*self),
@@ -70,6 +328,8 @@ impl PixelChunk for Pixel8 {
     }
 }
 
+impl_bit_ops!(Pixel8);
+
 impl IntoIterator for Pixel8 {
     type Item = Pixel8;
     type IntoIter = PixelChunkIterator<Self>;
@@ -99,7 +359,7 @@ impl From<Pixel8> for u8 {
 // Pixel4 contains 2 pixels in a single byte
 // the first pixel is the high 4 bits, the second pixel is the low 4 bits
 // when used as an argument as a singular pixel, the pixel in the lowest 4 bits is used
-#[derive(Debug, PartialEq, Clone, Copy, Default)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
 pub struct Pixel4 {
     pub value: u8,
 }
@@ -112,6 +372,47 @@ impl PixelChunk for Pixel4 {
         2
     }
 
+    #[inline]
+    fn pixel_mask(index: usize) -> Self {
+        match index {
+            0 => Pixel4 { value: 0xF0 },
+            1 => Pixel4 { value: 0x0F },
+            _ => Pixel4 { value: 0 },
+        }
+    }
+
+    fn match_mask(self, pixel: Self::PixelType) -> Self {
+        // fold each nibble of the difference down into its lowest bit, so we get one bit per mismatched pixel
+        let diff = self.value ^ Self::filled_pixel(pixel).value;
+        let diff = diff | (diff >> 2);
+        let diff = (diff | (diff >> 1)) & 0x11;
+        Pixel4 {
+            value: !(diff * 0xF),
+        }
+    }
+
+    #[inline]
+    fn shift_left(self, count: usize) -> Self {
+        Pixel4 {
+            value: self.value.checked_shl(count as u32 * 4).unwrap_or(0),
+        }
+    }
+
+    #[inline]
+    fn shift_right(self, count: usize) -> Self {
+        Pixel4 {
+            value: self.value.checked_shr(count as u32 * 4).unwrap_or(0),
+        }
+    }
+
+    #[inline]
+    fn reverse_pixels(self) -> Self {
+        // swap the nibbles
+        Pixel4 {
+            value: self.value.rotate_left(4),
+        }
+    }
+
     fn get_pixel(&self, index: usize) -> Option<Self> {
         match index {
             0 => Some((self.value >> 4).into()),
@@ -134,6 +435,8 @@ impl PixelChunk for Pixel4 {
     }
 }
 
+impl_bit_ops!(Pixel4);
+
 impl IntoIterator for Pixel4 {
     type Item = Pixel4;
     type IntoIter = PixelChunkIterator<Self>;
@@ -161,6 +464,261 @@ impl From<Pixel4> for u8 {
     }
 }
 
+// Pixel1 contains 8 pixels in a single byte
+// the first pixel is the highest bit, the last pixel the lowest bit
+// when used as an argument as a singular pixel, the lowest bit is used
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Pixel1 {
+    pub value: u8,
+}
+
+impl PixelChunk for Pixel1 {
+    type PixelType = Pixel1;
+
+    #[inline]
+    fn pixels() -> usize {
+        8
+    }
+
+    fn get_pixel(&self, index: usize) -> Option<Self> {
+        match index {
+            0..=7 => Some(((self.value >> (7 - index)) & 1).into()),
+            _ => None,
+        }
+    }
+
+    fn set_pixel(&mut self, index: usize, pixel: Self::PixelType) {
+        if index < 8 {
+            let bit = 0x80 >> index;
+            if pixel.value & 1 != 0 {
+                self.value |= bit;
+            } else {
+                self.value &= !bit;
+            }
+        }
+    }
+
+    #[inline]
+    fn pixel_mask(index: usize) -> Self {
+        Pixel1 {
+            value: 0x80u8.checked_shr(index as u32).unwrap_or(0),
+        }
+    }
+
+    #[inline]
+    fn range_mask(range: Range<usize>) -> Self {
+        let start = range.start.min(8) as u32;
+        let end = range.end.min(8) as u32;
+        if start >= end {
+            return Pixel1 { value: 0 };
+        }
+        Pixel1 {
+            value: (0xFFu8 >> start) & !0xFFu8.checked_shr(end).unwrap_or(0),
+        }
+    }
+
+    #[inline]
+    fn mask_from_flags(flags: Pixel1) -> Self {
+        flags
+    }
+
+    #[inline]
+    fn match_mask(self, pixel: Self::PixelType) -> Self {
+        if pixel.value & 1 != 0 {
+            self
+        } else {
+            !self
+        }
+    }
+
+    #[inline]
+    fn shift_left(self, count: usize) -> Self {
+        Pixel1 {
+            value: self.value.checked_shl(count as u32).unwrap_or(0),
+        }
+    }
+
+    #[inline]
+    fn shift_right(self, count: usize) -> Self {
+        Pixel1 {
+            value: self.value.checked_shr(count as u32).unwrap_or(0),
+        }
+    }
+
+    #[inline]
+    fn reverse_pixels(self) -> Self {
+        Pixel1 {
+            value: self.value.reverse_bits(),
+        }
+    }
+}
+
+impl_bit_ops!(Pixel1);
+
+impl IntoIterator for Pixel1 {
+    type Item = Pixel1;
+    type IntoIter = PixelChunkIterator<Self>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        PixelChunkIterator {
+            chunk: self,
+            index: 0,
+        }
+    }
+}
+
+impl From<u8> for Pixel1 {
+    #[inline]
+    fn from(value: u8) -> Self {
+        Pixel1 { value }
+    }
+}
+
+impl From<Pixel1> for u8 {
+    #[inline]
+    fn from(pixel: Pixel1) -> u8 {
+        pixel.value
+    }
+}
+
+// Pixel2 contains 4 pixels in a single byte
+// the first pixel is the highest 2 bits, the last pixel the lowest 2 bits
+// when used as an argument as a singular pixel, the lowest 2 bits are used
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Pixel2 {
+    pub value: u8,
+}
+
+impl PixelChunk for Pixel2 {
+    type PixelType = Pixel2;
+
+    #[inline]
+    fn pixels() -> usize {
+        4
+    }
+
+    fn get_pixel(&self, index: usize) -> Option<Self> {
+        match index {
+            0..=3 => Some(((self.value >> (6 - index * 2)) & 0x3).into()),
+            _ => None,
+        }
+    }
+
+    fn set_pixel(&mut self, index: usize, pixel: Self::PixelType) {
+        if index < 4 {
+            let shift = 6 - index * 2;
+            self.value = (self.value & !(0x3 << shift)) | ((pixel.value & 0x3) << shift);
+        }
+    }
+
+    #[inline]
+    fn pixel_mask(index: usize) -> Self {
+        Pixel2 {
+            value: 0xC0u8.checked_shr(index as u32 * 2).unwrap_or(0),
+        }
+    }
+
+    fn match_mask(self, pixel: Self::PixelType) -> Self {
+        // fold each pair of bits of the difference into its lowest bit
+        let diff = self.value ^ Self::filled_pixel(pixel).value;
+        let diff = (diff | (diff >> 1)) & 0x55;
+        Pixel2 {
+            value: !(diff * 0x3),
+        }
+    }
+
+    #[inline]
+    fn shift_left(self, count: usize) -> Self {
+        Pixel2 {
+            value: self.value.checked_shl(count as u32 * 2).unwrap_or(0),
+        }
+    }
+
+    #[inline]
+    fn shift_right(self, count: usize) -> Self {
+        Pixel2 {
+            value: self.value.checked_shr(count as u32 * 2).unwrap_or(0),
+        }
+    }
+
+    #[inline]
+    fn reverse_pixels(self) -> Self {
+        // swap the nibbles, then the pairs within each nibble
+        let value = self.value.rotate_left(4);
+        Pixel2 {
+            value: ((value >> 2) & 0x33) | ((value & 0x33) << 2),
+        }
+    }
+}
+
+impl_bit_ops!(Pixel2);
+
+impl IntoIterator for Pixel2 {
+    type Item = Pixel2;
+    type IntoIter = PixelChunkIterator<Self>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        PixelChunkIterator {
+            chunk: self,
+            index: 0,
+        }
+    }
+}
+
+impl From<u8> for Pixel2 {
+    #[inline]
+    fn from(value: u8) -> Self {
+        Pixel2 { value }
+    }
+}
+
+impl From<Pixel2> for u8 {
+    #[inline]
+    fn from(pixel: Pixel2) -> u8 {
+        pixel.value
+    }
+}
+
+// Chunks that are stored in a single byte
+// these can be processed a whole chunk at a time using 256 entry lookup tables
+pub trait ByteChunk: PixelChunk {
+    fn to_byte(self) -> u8;
+
+    fn from_byte(value: u8) -> Self;
+}
+
+macro_rules! impl_byte_chunk {
+    ($chunk:ident) => {
+        impl ByteChunk for $chunk {
+            #[inline]
+            fn to_byte(self) -> u8 {
+                self.value
+            }
+
+            #[inline]
+            fn from_byte(value: u8) -> Self {
+                $chunk { value }
+            }
+        }
+    };
+}
+
+impl_byte_chunk!(Pixel8);
+impl_byte_chunk!(Pixel4);
+impl_byte_chunk!(Pixel2);
+impl_byte_chunk!(Pixel1);
+
+// 32-bit colour with 8 bits of alpha, red, green and blue, from most significant to least
+single_pixel_chunk!(Argb8888, u32, 0x00FF_FFFF);
+
+// 16-bit colour with 4 bits of alpha, red, green and blue, from most significant to least
+single_pixel_chunk!(Argb4444, u16, 0x0FFF);
+
+// Agon bitmap format, with 2 bits of red, green, blue and alpha, from least significant to most
+single_pixel_chunk!(Rgba2222, u8, 0b0011_1111);
+
+impl_byte_chunk!(Rgba2222);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -244,6 +802,163 @@ mod tests {
         assert_eq!(test, 0xC);
     }
 
+    #[test]
+    fn can_get_pixel_masks() {
+        assert_eq!(Pixel4::pixel_mask(0).value, 0xF0);
+        assert_eq!(Pixel4::pixel_mask(1).value, 0x0F);
+        assert_eq!(Pixel4::range_mask(0..2).value, 0xFF);
+        assert_eq!(Pixel4::range_mask(1..2).value, 0x0F);
+        assert_eq!(Pixel4::range_mask(1..1).value, 0x00);
+        assert_eq!(Pixel8::range_mask(0..1).value, 0xFF);
+        assert_eq!(Pixel8::range_mask(1..1).value, 0x00);
+    }
+
+    #[test]
+    fn can_combine_chunks_bitwise() {
+        let a = Pixel4 { value: 0xC3 };
+        let b = Pixel4 { value: 0x5A };
+        assert_eq!((a & b).value, 0x42);
+        assert_eq!((a | b).value, 0xDB);
+        assert_eq!((a ^ b).value, 0x99);
+        assert_eq!((!a).value, 0x3C);
+    }
+
+    #[test]
+    fn can_shift_pixels_in_chunks() {
+        let pixel = Pixel4 { value: 0xAB };
+        assert_eq!(pixel.shift_left(0).value, 0xAB);
+        assert_eq!(pixel.shift_left(1).value, 0xB0);
+        assert_eq!(pixel.shift_left(2).value, 0x00);
+        assert_eq!(pixel.shift_right(1).value, 0x0A);
+        assert_eq!(pixel.shift_right(5).value, 0x00);
+
+        let pixel = Pixel8 { value: 0xAB };
+        assert_eq!(pixel.shift_left(0).value, 0xAB);
+        assert_eq!(pixel.shift_left(1).value, 0x00);
+        assert_eq!(pixel.shift_right(1).value, 0x00);
+    }
+
+    #[test]
+    fn can_get_match_masks() {
+        let pixel = Pixel4 { value: 0x3A };
+        assert_eq!(pixel.match_mask(0x3.into()).value, 0xF0);
+        assert_eq!(pixel.match_mask(0xA.into()).value, 0x0F);
+        assert_eq!(pixel.match_mask(0x2.into()).value, 0x00);
+        assert_eq!(Pixel4 { value: 0x77 }.match_mask(0x7.into()).value, 0xFF);
+        for value in 0..=255u8 {
+            for key in 0..16u8 {
+                let chunk = Pixel4 { value };
+                let mut expected = 0;
+                if value >> 4 == key {
+                    expected |= 0xF0;
+                }
+                if value & 0xF == key {
+                    expected |= 0x0F;
+                }
+                assert_eq!(chunk.match_mask(key.into()).value, expected);
+            }
+        }
+
+        let pixel = Pixel8 { value: 0x3A };
+        assert_eq!(pixel.match_mask(0x3A.into()).value, 0xFF);
+        assert_eq!(pixel.match_mask(0x3B.into()).value, 0x00);
+    }
+
+    #[test]
+    fn can_use_pixel1() {
+        let mut pixel = Pixel1 { value: 0b1010_0001 };
+        assert_eq!(pixel.get_pixel(0), Some(1.into()));
+        assert_eq!(pixel.get_pixel(1), Some(0.into()));
+        assert_eq!(pixel.get_pixel(7), Some(1.into()));
+        assert_eq!(pixel.get_pixel(8), None);
+        pixel.set_pixel(1, 1.into());
+        pixel.set_pixel(7, 0.into());
+        assert_eq!(pixel.value, 0b1110_0000);
+        assert_eq!(Pixel1::filled_pixel(1.into()).value, 0xFF);
+        assert_eq!(Pixel1::range_mask(2..5).value, 0b0011_1000);
+        assert_eq!(Pixel1::range_mask(0..8).value, 0xFF);
+        assert_eq!(pixel.match_mask(0.into()).value, 0b0001_1111);
+        assert_eq!(pixel.shift_left(2).value, 0b1000_0000);
+        assert_eq!(pixel.shift_right(8).value, 0);
+        assert_eq!(pixel.into_iter().count(), 8);
+    }
+
+    #[test]
+    fn can_use_pixel2() {
+        let mut pixel = Pixel2 {
+            value: 0b11_10_01_00,
+        };
+        assert_eq!(pixel.get_pixel(0), Some(3.into()));
+        assert_eq!(pixel.get_pixel(2), Some(1.into()));
+        assert_eq!(pixel.get_pixel(4), None);
+        pixel.set_pixel(3, 2.into());
+        pixel.set_pixel(0, 0.into());
+        assert_eq!(pixel.value, 0b00_10_01_10);
+        assert_eq!(Pixel2::filled_pixel(1.into()).value, 0x55);
+        assert_eq!(Pixel2::range_mask(1..3).value, 0b00_11_11_00);
+        assert_eq!(pixel.match_mask(2.into()).value, 0b00_11_00_11);
+        assert_eq!(pixel.shift_left(1).value, 0b10_01_10_00);
+        assert_eq!(pixel.shift_right(3).value, 0b00_00_00_00);
+        assert_eq!(
+            Pixel2::mask_from_flags(Pixel1 { value: 0xA0 }).value,
+            0b11_00_11_00
+        );
+        for value in 0..=255u8 {
+            for key in 0..4u8 {
+                let chunk = Pixel2 { value };
+                let mut expected = Pixel2::default();
+                for i in 0..4 {
+                    if chunk.get_pixel(i) == Some(key.into()) {
+                        expected = expected | Pixel2::pixel_mask(i);
+                    }
+                }
+                assert_eq!(chunk.match_mask(key.into()), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn can_get_masks_from_flags() {
+        let flags = Pixel1 { value: 0b0100_0000 };
+        assert_eq!(Pixel4::mask_from_flags(flags).value, 0x0F);
+        assert_eq!(Pixel8::mask_from_flags(flags).value, 0x00);
+        assert_eq!(Pixel1::mask_from_flags(flags).value, 0b0100_0000);
+        assert_eq!(Pixel8::mask_from_flags(Pixel1 { value: 0x80 }).value, 0xFF);
+    }
+
+    #[test]
+    fn can_reverse_pixels() {
+        assert_eq!(Pixel8 { value: 0x12 }.reverse_pixels().value, 0x12);
+        assert_eq!(Pixel4 { value: 0x12 }.reverse_pixels().value, 0x21);
+        assert_eq!(
+            Pixel2 {
+                value: 0b00_01_10_11
+            }
+            .reverse_pixels()
+            .value,
+            0b11_10_01_00
+        );
+        assert_eq!(
+            Pixel1 { value: 0b1100_0010 }.reverse_pixels().value,
+            0b0100_0011
+        );
+        assert_eq!(Argb4444::from(0xF00F).reverse_pixels().value, 0xF00F);
+    }
+
+    #[test]
+    fn can_use_single_pixel_chunks() {
+        let mut pixel: Argb8888 = 0x80FF8000.into();
+        assert_eq!(Argb8888::pixels(), 1);
+        assert_eq!(pixel.get_pixel(0), Some(0x80FF8000.into()));
+        assert_eq!(pixel.get_pixel(1), None);
+        pixel.set_pixel(0, 0x12345678.into());
+        assert_eq!(u32::from(pixel), 0x12345678);
+        assert_eq!(Argb8888::pixel_mask(0).value, 0xFFFFFFFF);
+        assert_eq!(Argb4444::filled_pixel(0xF00F.into()).value, 0xF00F);
+        assert_eq!(Rgba2222::from(0xC3).match_mask(0xC3.into()).value, 0xFF);
+        assert_eq!(Rgba2222::from(0xC3).match_mask(0xC0.into()).value, 0x00);
+    }
+
     #[test]
     fn can_iterate_over_pixel8() {
         let pixel = Pixel8 { value: 0xAB };
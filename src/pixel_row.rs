@@ -1,143 +1,1384 @@
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
 use std::ops::{Index, IndexMut, Range};
+use std::sync::Arc;
 
+use crate::colour::{Blend, Colour, ColourLut, ColourMap, ColourPixel};
+use crate::error::Error;
+use crate::pattern::{LinePattern, Pattern};
 use crate::pixel_formats::*;
+use crate::plot_mode::PlotMode;
+use crate::row_slice::{RowSlice, RowSliceMut};
 
-#[derive(Clone, Debug, PartialEq)]
+// Rows compare and hash by their visible pixels only, ignoring padding
+#[derive(Clone, Debug)]
 pub struct PixelRow<T: PixelChunk> {
-    // TODO change pixel_chunks vector to be a kind of reference
-    // This will allow cheap cloning of the row
-    pixel_chunks: Vec<T>,
+    // Shared between clones of the row, and copied on the first write to a shared row
+    pixel_chunks: Arc<Vec<T>>,
     // Amount of pixels to ignore at the start of the row
     pad_left: usize,
     // Amount of pixels to ignore at the end of the row
     pad_right: usize,
 }
 
+// How a source slice is fitted to a destination range when their lengths differ
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SourceFit {
+    // tile the source across the whole range
+    Repeat,
+    // write as many pixels as both the source and range allow, leaving the rest of the range untouched
+    Truncate,
+    // return an error if the lengths differ, leaving the row untouched
+    Exact,
+}
+
+// Restricts which bits of a row a write may change
+// `bits` is a chunk mask of the bits of each pixel value that may change
+// and if a `stencil` row is present, pixels may only change where the stencil pixel is set
+#[derive(Clone, Copy)]
+pub(crate) struct WriteMask<'a, T: PixelChunk> {
+    bits: T,
+    stencil: Option<&'a PixelRow<Pixel1>>,
+}
+
+impl<'a, T: PixelChunk<PixelType = T>> WriteMask<'a, T> {
+    pub(crate) fn all() -> WriteMask<'a, T> {
+        WriteMask {
+            bits: !T::default(),
+            stencil: None,
+        }
+    }
+
+    // `write_mask` is a pixel value, so the same bits apply to every pixel
+    pub(crate) fn new(write_mask: T) -> WriteMask<'a, T> {
+        WriteMask {
+            bits: T::filled_pixel(write_mask),
+            stencil: None,
+        }
+    }
+
+    pub(crate) fn with_stencil(self, stencil: &'a PixelRow<Pixel1>) -> WriteMask<'a, T> {
+        WriteMask {
+            stencil: Some(stencil),
+            ..self
+        }
+    }
+
+    // whether the pixel at visible `index` may be written
+    fn allows(&self, index: usize) -> bool {
+        match self.stencil {
+            Some(stencil) => stencil.pixel(index) == Some(Pixel1 { value: 1 }),
+            None => true,
+        }
+    }
+
+    // mask for the chunk at `chunk_index` in a row with `pad_left` pixels of left padding
+    fn chunk_mask(&self, chunk_index: usize, pad_left: usize) -> T {
+        match self.stencil {
+            Some(stencil) => {
+                let index = (chunk_index * T::pixels()) as isize - pad_left as isize;
+                self.bits & T::mask_from_flags(stencil.visible_chunk_at(index))
+            }
+            None => self.bits,
+        }
+    }
+}
+
+// gets a chunk made up of the pixels starting at pixel `offset` in `chunks`
+// pixels that lie outside of the chunks are returned as zero
+fn chunk_at<T: PixelChunk>(chunks: &[T], offset: isize) -> T {
+    let pixels = T::pixels() as isize;
+    let chunk_index = offset.div_euclid(pixels);
+    let shift = offset.rem_euclid(pixels) as usize;
+    let chunk = |index: isize| -> T {
+        if index < 0 {
+            T::default()
+        } else {
+            chunks.get(index as usize).copied().unwrap_or_default()
+        }
+    };
+    if shift == 0 {
+        chunk(chunk_index)
+    } else {
+        chunk(chunk_index).shift_left(shift)
+            | chunk(chunk_index + 1).shift_right(T::pixels() - shift)
+    }
+}
+
+// whether `pixel` holds a single pixel value, with no bits set beyond the width of one pixel
+fn is_pixel_value<T: PixelChunk<PixelType = T>>(pixel: T) -> bool {
+    let mut chunk = T::default();
+    chunk.set_pixel(0, pixel);
+    chunk.get_pixel(0) == Some(pixel)
+}
+
+// Walks the chunks covering a range of raw pixels, yielding the index of each chunk
+// along with a mask of the bits in that chunk that lie within the range
+struct ChunkSpans<T: PixelChunk> {
+    next_chunk: usize,
+    end_chunk: usize,
+    start: usize,
+    end: usize,
+    _chunk: PhantomData<T>,
+}
+
+impl<T: PixelChunk> ChunkSpans<T> {
+    fn new(range: Range<usize>) -> ChunkSpans<T> {
+        let (next_chunk, end_chunk) = if range.is_empty() {
+            (0, 0)
+        } else {
+            (range.start / T::pixels(), range.end.div_ceil(T::pixels()))
+        };
+        ChunkSpans {
+            next_chunk,
+            end_chunk,
+            start: range.start,
+            end: range.end,
+            _chunk: PhantomData,
+        }
+    }
+}
+
+impl<T: PixelChunk> ChunkSpans<T> {
+    fn is_empty(&self) -> bool {
+        self.next_chunk == self.end_chunk
+    }
+
+    fn span(&self, chunk_index: usize) -> (usize, T) {
+        let chunk_start = chunk_index * T::pixels();
+        let from = self.start.saturating_sub(chunk_start);
+        let to = (self.end - chunk_start).min(T::pixels());
+        if from == 0 && to == T::pixels() {
+            (chunk_index, !T::default())
+        } else {
+            (chunk_index, T::range_mask(from..to))
+        }
+    }
+}
+
+impl<T: PixelChunk> Iterator for ChunkSpans<T> {
+    type Item = (usize, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next_chunk >= self.end_chunk {
+            return None;
+        }
+        self.next_chunk += 1;
+        Some(self.span(self.next_chunk - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end_chunk - self.next_chunk;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T: PixelChunk> DoubleEndedIterator for ChunkSpans<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.next_chunk >= self.end_chunk {
+            return None;
+        }
+        self.end_chunk -= 1;
+        Some(self.span(self.end_chunk))
+    }
+}
+
+// Iterates over the chunks of a row that cover a range of pixels, paired with a mask of the bits
+// in each chunk that belong to the range.  Middle chunks have a full mask, and chunks at either end
+// of the range are partial, with the row's padding already taken into account
+pub struct RowChunks<'a, T: PixelChunk> {
+    chunks: &'a [T],
+    spans: ChunkSpans<T>,
+}
+
+impl<T: PixelChunk> Iterator for RowChunks<'_, T> {
+    type Item = (T, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (chunk_index, mask) = self.spans.next()?;
+        Some((self.chunks[chunk_index], mask))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.spans.size_hint()
+    }
+}
+
+impl<T: PixelChunk> DoubleEndedIterator for RowChunks<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (chunk_index, mask) = self.spans.next_back()?;
+        Some((self.chunks[chunk_index], mask))
+    }
+}
+
+impl<T: PixelChunk> ExactSizeIterator for RowChunks<'_, T> {}
+
+// As `RowChunks`, but allowing each chunk to be changed in place
+// Callers should only change the bits of a chunk that are set in its mask
+pub struct RowChunksMut<'a, T: PixelChunk> {
+    chunks: std::slice::IterMut<'a, T>,
+    spans: ChunkSpans<T>,
+}
+
+impl<'a, T: PixelChunk> Iterator for RowChunksMut<'a, T> {
+    type Item = (&'a mut T, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, mask) = self.spans.next()?;
+        Some((self.chunks.next()?, mask))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.spans.size_hint()
+    }
+}
+
+impl<T: PixelChunk> DoubleEndedIterator for RowChunksMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (_, mask) = self.spans.next_back()?;
+        Some((self.chunks.next_back()?, mask))
+    }
+}
+
+impl<T: PixelChunk> ExactSizeIterator for RowChunksMut<'_, T> {}
+
+// Where existing pixels are kept when a row changes size
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Align {
+    // keep the first pixel in place
+    #[default]
+    Start,
+    // keep the pixels centred, with any odd pixel added or removed at the end
+    Centre,
+    // keep the last pixel in place
+    End,
+}
+
+impl Align {
+    // how far content moves when a length changes from `old` to `new`
+    pub(crate) fn offset(self, old: usize, new: usize) -> isize {
+        let change = new as isize - old as isize;
+        match self {
+            Align::Start => 0,
+            Align::Centre => change / 2,
+            Align::End => change,
+        }
+    }
+}
+
+// Direction to search along a row
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Direction {
+    // towards index 0
+    Left,
+    // towards the end of the row
+    Right,
+}
+
+impl<T: PixelChunk> PixelRow<T> {
+    // the chunks of the row for writing, first taking a copy if they are shared with another row
+    fn chunks_mut(&mut self) -> &mut Vec<T> {
+        Arc::make_mut(&mut self.pixel_chunks)
+    }
+
+    // whether this row shares its storage with `other`, as it will after cloning until either is changed
+    pub fn shares_storage_with(&self, other: &PixelRow<T>) -> bool {
+        Arc::ptr_eq(&self.pixel_chunks, &other.pixel_chunks)
+    }
+}
+
+// Iterates over the visible pixels of a row, or a range of them
+// Can be run from either end, and can skip ahead without visiting each pixel
 pub struct PixelRowIterator<'a, T: PixelChunk> {
     row: &'a PixelRow<T>,
+    // raw index of the next pixel from the front
     pixel_index: usize,
-    chunk_index: usize,
-    chunk_iterator: <T as std::iter::IntoIterator>::IntoIter,
+    // raw index after the next pixel from the back
+    end_index: usize,
+}
+
+impl<T: PixelChunk> PixelRowIterator<'_, T> {
+    fn raw_pixel(&self, index: usize) -> Option<T> {
+        self.row.pixel_chunks[index / T::pixels()].get_pixel(index % T::pixels())
+    }
 }
 
 impl<'a, T: PixelChunk> Iterator for PixelRowIterator<'a, T> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.pixel_index >= (self.row.pixel_chunks.len() * T::pixels()) - self.row.pad_right {
+        if self.pixel_index >= self.end_index {
+            return None;
+        }
+        let pixel = self.raw_pixel(self.pixel_index);
+        self.pixel_index += 1;
+        pixel
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.pixel_index = self.pixel_index.saturating_add(n).min(self.end_index);
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end_index - self.pixel_index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T: PixelChunk> DoubleEndedIterator for PixelRowIterator<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.pixel_index >= self.end_index {
             return None;
         }
+        self.end_index -= 1;
+        self.raw_pixel(self.end_index)
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.end_index = self.end_index.saturating_sub(n).max(self.pixel_index);
+        self.next_back()
+    }
+}
+
+impl<T: PixelChunk> ExactSizeIterator for PixelRowIterator<'_, T> {}
+
+// A single pixel within a row that can be read and written in place
+// pixels share their chunk with their neighbours, so the chunk is held in a `Cell`
+pub struct PixelMut<'a, T: PixelChunk> {
+    chunk: &'a Cell<T>,
+    index: usize,
+}
+
+impl<T: PixelChunk<PixelType = T>> PixelMut<'_, T> {
+    pub fn get(&self) -> T {
+        self.chunk.get().get_pixel(self.index).unwrap()
+    }
+
+    // set the pixel - the `pixel` value will use the "default" pixel in the provided pixel chunk
+    pub fn set(&self, pixel: T) {
+        let mut chunk = self.chunk.get();
+        chunk.set_pixel(self.index, pixel);
+        self.chunk.set(chunk);
+    }
+}
+
+// Iterates over the visible pixels of a row, allowing each to be changed in place
+pub struct PixelRowIteratorMut<'a, T: PixelChunk> {
+    chunks: &'a [Cell<T>],
+    pixel_index: usize,
+    end_index: usize,
+}
+
+impl<'a, T: PixelChunk> PixelRowIteratorMut<'a, T> {
+    fn raw_pixel(&self, index: usize) -> PixelMut<'a, T> {
+        PixelMut {
+            chunk: &self.chunks[index / T::pixels()],
+            index: index % T::pixels(),
+        }
+    }
+}
+
+impl<'a, T: PixelChunk> Iterator for PixelRowIteratorMut<'a, T> {
+    type Item = PixelMut<'a, T>;
 
-        let mut pixel = self.chunk_iterator.next();
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pixel_index >= self.end_index {
+            return None;
+        }
+        let pixel = self.raw_pixel(self.pixel_index);
         self.pixel_index += 1;
-        if pixel.is_none() {
-            self.chunk_index += 1;
-            if self.chunk_index < self.row.pixel_chunks.len() {
-                self.chunk_iterator = self.row.pixel_chunks[self.chunk_index].into_iter();
-                pixel = self.chunk_iterator.next();
-            }
+        Some(pixel)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.pixel_index = self.pixel_index.saturating_add(n).min(self.end_index);
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end_index - self.pixel_index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T: PixelChunk> DoubleEndedIterator for PixelRowIteratorMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.pixel_index >= self.end_index {
+            return None;
         }
+        self.end_index -= 1;
+        Some(self.raw_pixel(self.end_index))
+    }
 
-        pixel
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.end_index = self.end_index.saturating_sub(n).max(self.pixel_index);
+        self.next_back()
     }
 }
 
+impl<T: PixelChunk> ExactSizeIterator for PixelRowIteratorMut<'_, T> {}
+
 impl<T: PixelChunk<PixelType = T>> PixelRow<T> {
     pub fn new(size: usize) -> PixelRow<T> {
         Self::new_with(size, Default::default())
     }
 
     pub fn new_with(size: usize, pixel: T) -> PixelRow<T> {
-        let pad = size % T::pixels();
-        let real_size = size / T::pixels() + if pad > 0 { 1 } else { 0 };
+        let real_size = size.div_ceil(T::pixels());
         PixelRow {
-            pixel_chunks: vec![pixel; real_size],
+            pixel_chunks: Arc::new(vec![pixel; real_size]),
             pad_left: 0,
-            pad_right: pad,
+            pad_right: real_size * T::pixels() - size,
         }
     }
 
     // is this wanted/needed?
     pub fn from_vec(pixel_chunks: Vec<T>) -> PixelRow<T> {
         PixelRow {
-            pixel_chunks,
+            pixel_chunks: Arc::new(pixel_chunks),
             pad_left: 0,
             pad_right: 0,
         }
     }
 
+    // create a row with `width` visible pixels, stored in at least `total_width` pixels
+    // the visible window can then be scrolled across the stored pixels without copying them
+    pub fn new_scrollable(width: usize, total_width: usize) -> PixelRow<T> {
+        let mut row = Self::new(width.max(total_width));
+        row.pad_right = row.stored_width() - width;
+        row
+    }
+
     pub fn width(&self) -> usize {
         self.pixel_chunks.len() * T::pixels() - self.pad_left - self.pad_right
     }
 
+    // number of pixels held by the row, including those scrolled out of view
+    pub fn stored_width(&self) -> usize {
+        self.pixel_chunks.len() * T::pixels()
+    }
+
+    // offset of the first visible pixel into the stored pixels
+    pub fn scroll_offset(&self) -> usize {
+        self.pad_left
+    }
+
+    pub fn max_scroll_offset(&self) -> usize {
+        self.pad_left + self.pad_right
+    }
+
+    // move the visible window, keeping its width, clamped to the stored pixels
+    pub fn set_scroll_offset(&mut self, offset: usize) {
+        let hidden = self.max_scroll_offset();
+        self.pad_left = offset.min(hidden);
+        self.pad_right = hidden - self.pad_left;
+    }
+
+    // move the visible window by `delta` pixels, clamped to the stored pixels
+    pub fn scroll_by(&mut self, delta: isize) {
+        self.set_scroll_offset(self.pad_left.saturating_add_signed(delta));
+    }
+
     pub fn pixel(&self, index: usize) -> Option<T> {
+        if index >= self.width() {
+            return None;
+        }
         let actual_index = index + self.pad_left;
         let chunk = self.pixel_chunks.get(actual_index / T::pixels())?;
         chunk.get_pixel(actual_index % T::pixels())
     }
 
     // set a pixel - the `pixel` value will use the "default" pixel in the provided pixel chunk
+    // pixels outside of the row are ignored
     pub fn set_pixel(&mut self, index: usize, pixel: T) {
+        if index >= self.width() {
+            return;
+        }
         let actual_index = index + self.pad_left;
-        let chunk = self
-            .pixel_chunks
-            .get_mut(actual_index / T::pixels())
-            .unwrap();
-        chunk.set_pixel(actual_index % T::pixels(), pixel);
+        self.chunks_mut()[actual_index / T::pixels()].set_pixel(actual_index % T::pixels(), pixel);
     }
 
     pub fn fill_range(&mut self, range: Range<usize>, pixel: T) {
-        if T::pixels() == 1 || range.len() < (T::pixels() * 2) {
-            // fill can be simplistic
-            for i in range {
-                self.set_pixel(i, pixel);
+        self.fill_range_with_chunk(range, T::filled_pixel(pixel));
+    }
+
+    pub fn fill_range_with_chunk(&mut self, range: Range<usize>, chunk: T) {
+        // fills a range with given chunk, where fill is aligned by chunk
+        self.plot_range_with_chunk(range, chunk, PlotMode::Set);
+    }
+
+    // set a pixel, only changing the bits of the pixel value that are set in `write_mask`
+    pub fn set_pixel_masked(&mut self, index: usize, pixel: T, write_mask: T) {
+        self.plot_pixel_masked(index, pixel, PlotMode::Set, write_mask);
+    }
+
+    // fill a range, only changing the bits of each pixel value that are set in `write_mask`
+    pub fn fill_range_masked(&mut self, range: Range<usize>, pixel: T, write_mask: T) {
+        self.plot_range_masked(range, pixel, PlotMode::Set, write_mask);
+    }
+
+    // plot a pixel using the given logical operation
+    pub fn plot_pixel(&mut self, index: usize, pixel: T, mode: PlotMode) {
+        self.write_pixel(index, pixel, mode, &WriteMask::all());
+    }
+
+    // plot a pixel, only changing the bits of the pixel value that are set in `write_mask`
+    pub fn plot_pixel_masked(&mut self, index: usize, pixel: T, mode: PlotMode, write_mask: T) {
+        self.write_pixel(index, pixel, mode, &WriteMask::new(write_mask));
+    }
+
+    // plot a range of pixels using the given logical operation
+    pub fn plot_range(&mut self, range: Range<usize>, pixel: T, mode: PlotMode) {
+        self.plot_range_with_chunk(range, T::filled_pixel(pixel), mode);
+    }
+
+    // plot a range of pixels, only changing the bits of each pixel value that are set in `write_mask`
+    pub fn plot_range_masked(
+        &mut self,
+        range: Range<usize>,
+        pixel: T,
+        mode: PlotMode,
+        write_mask: T,
+    ) {
+        self.plot_range_with_chunk_masked(range, T::filled_pixel(pixel), mode, write_mask);
+    }
+
+    // plot a range of pixels, only changing pixels where the matching pixel in `stencil` is set
+    // the stencil is aligned with the visible pixels of this row
+    pub fn plot_range_stencilled(
+        &mut self,
+        range: Range<usize>,
+        pixel: T,
+        mode: PlotMode,
+        stencil: &PixelRow<Pixel1>,
+    ) {
+        let mask = WriteMask::all().with_stencil(stencil);
+        self.write_range(range, T::filled_pixel(pixel), mode, &mask);
+    }
+
+    // plot a range using the given chunk, where the chunk is aligned with the row's chunks
+    pub fn plot_range_with_chunk(&mut self, range: Range<usize>, chunk: T, mode: PlotMode) {
+        self.write_range(range, chunk, mode, &WriteMask::all());
+    }
+
+    // plot a range using the given chunk, only changing the bits of each pixel value that are set in `write_mask`
+    pub fn plot_range_with_chunk_masked(
+        &mut self,
+        range: Range<usize>,
+        chunk: T,
+        mode: PlotMode,
+        write_mask: T,
+    ) {
+        self.write_range(range, chunk, mode, &WriteMask::new(write_mask));
+    }
+
+    pub(crate) fn write_pixel(
+        &mut self,
+        index: usize,
+        pixel: T,
+        mode: PlotMode,
+        write_mask: &WriteMask<T>,
+    ) {
+        if index >= self.width() || !write_mask.allows(index) {
+            return;
+        }
+        let actual_index = index + self.pad_left;
+        let mask = T::pixel_mask(actual_index % T::pixels()) & write_mask.bits;
+        if mask == T::default() {
+            return;
+        }
+        let mut source = T::default();
+        source.set_pixel(actual_index % T::pixels(), pixel);
+        let chunk = &mut self.chunks_mut()[actual_index / T::pixels()];
+        *chunk = mode.apply_masked(*chunk, source, mask);
+    }
+
+    pub(crate) fn write_range(
+        &mut self,
+        range: Range<usize>,
+        chunk: T,
+        mode: PlotMode,
+        write_mask: &WriteMask<T>,
+    ) {
+        self.plot_chunks(range, mode, write_mask, |_| chunk);
+    }
+
+    // fill a range with a repeating pattern
+    // the pattern is aligned with the start of this row, and `y` picks the pattern row to use
+    pub fn fill_range_with_pattern(&mut self, range: Range<usize>, pattern: &Pattern<T>, y: usize) {
+        self.plot_range_with_pattern(range, pattern, y, PlotMode::Set);
+    }
+
+    // plot a range with a repeating pattern using the given logical operation
+    pub fn plot_range_with_pattern(
+        &mut self,
+        range: Range<usize>,
+        pattern: &Pattern<T>,
+        y: usize,
+        mode: PlotMode,
+    ) {
+        self.write_range_with_pattern(range, pattern, y, mode, &WriteMask::all());
+    }
+
+    pub(crate) fn write_range_with_pattern(
+        &mut self,
+        range: Range<usize>,
+        pattern: &Pattern<T>,
+        y: usize,
+        mode: PlotMode,
+        write_mask: &WriteMask<T>,
+    ) {
+        let pad_left = self.pad_left as isize;
+        self.plot_chunks(range, mode, write_mask, |chunk_index| {
+            pattern.chunk_at((chunk_index * T::pixels()) as isize - pad_left, y)
+        });
+    }
+
+    // plot a pixel if the next pixel of a dotted line pattern is on
+    // the pattern is always moved on by one pixel
+    pub fn plot_pixel_dotted(
+        &mut self,
+        index: usize,
+        pixel: T,
+        mode: PlotMode,
+        pattern: &mut LinePattern,
+    ) {
+        self.write_pixel_dotted(index, pixel, mode, pattern, &WriteMask::all());
+    }
+
+    // plot a range of pixels from left to right, only where a dotted line pattern is on
+    // the pattern is moved on by the length of the range
+    pub fn plot_range_dotted(
+        &mut self,
+        range: Range<usize>,
+        pixel: T,
+        mode: PlotMode,
+        pattern: &mut LinePattern,
+    ) {
+        self.write_range_dotted(range, pixel, mode, pattern, &WriteMask::all());
+    }
+
+    pub(crate) fn write_pixel_dotted(
+        &mut self,
+        index: usize,
+        pixel: T,
+        mode: PlotMode,
+        pattern: &mut LinePattern,
+        write_mask: &WriteMask<T>,
+    ) {
+        if pattern.next_pixel() {
+            self.write_pixel(index, pixel, mode, write_mask);
+        }
+    }
+
+    pub(crate) fn write_range_dotted(
+        &mut self,
+        range: Range<usize>,
+        pixel: T,
+        mode: PlotMode,
+        pattern: &mut LinePattern,
+        write_mask: &WriteMask<T>,
+    ) {
+        let length = range.len();
+        let range = self.visible_range(range);
+        let clipped = length - range.len();
+        let chunk = T::filled_pixel(pixel);
+        let pad_left = self.pad_left;
+        let start = range.start + pad_left;
+        let end = range.end + pad_left;
+        self.plot_chunks_with_masks(range, mode, |chunk_index| {
+            let chunk_start = chunk_index * T::pixels();
+            let from = start.saturating_sub(chunk_start);
+            let to = (end - chunk_start).min(T::pixels());
+            let flags = Pixel1::from(pattern.next_flags(to - from) >> from);
+            let mask = write_mask.chunk_mask(chunk_index, pad_left) & T::mask_from_flags(flags);
+            (chunk, mask)
+        });
+        // keep the pattern in step for the part of the range past the end of the row
+        pattern.skip(clipped);
+    }
+
+    // copy pixels from `source_range` in `source` into this row, starting at `dest_start`
+    pub fn copy_from(
+        &mut self,
+        dest_start: usize,
+        source: &PixelRow<T>,
+        source_range: Range<usize>,
+    ) {
+        self.combine_from(dest_start, source, source_range, PlotMode::Set);
+    }
+
+    // combine pixels from `source_range` in `source` into this row, starting at `dest_start`
+    // the source and destination do not need to share the same alignment within their chunks
+    // the copy is clipped to the width of both rows
+    pub fn combine_from(
+        &mut self,
+        dest_start: usize,
+        source: &PixelRow<T>,
+        source_range: Range<usize>,
+        mode: PlotMode,
+    ) {
+        self.combine(
+            dest_start,
+            source,
+            source_range,
+            mode,
+            &WriteMask::all(),
+            None,
+        );
+    }
+
+    // combine pixels from another row, only changing the bits of each pixel value that are set in `write_mask`
+    pub fn combine_from_masked(
+        &mut self,
+        dest_start: usize,
+        source: &PixelRow<T>,
+        source_range: Range<usize>,
+        mode: PlotMode,
+        write_mask: T,
+    ) {
+        let write_mask = WriteMask::new(write_mask);
+        self.combine(dest_start, source, source_range, mode, &write_mask, None);
+    }
+
+    // copy pixels from another row, leaving the destination untouched where the source pixel is `key`
+    pub fn copy_from_keyed(
+        &mut self,
+        dest_start: usize,
+        source: &PixelRow<T>,
+        source_range: Range<usize>,
+        key: T,
+    ) {
+        self.combine_from_keyed(dest_start, source, source_range, PlotMode::Set, key);
+    }
+
+    // combine pixels from another row, leaving the destination untouched where the source pixel is `key`
+    pub fn combine_from_keyed(
+        &mut self,
+        dest_start: usize,
+        source: &PixelRow<T>,
+        source_range: Range<usize>,
+        mode: PlotMode,
+        key: T,
+    ) {
+        let write_mask = WriteMask::all();
+        self.combine(
+            dest_start,
+            source,
+            source_range,
+            mode,
+            &write_mask,
+            Some(key),
+        );
+    }
+
+    // shared implementation for all the row combining operations
+    pub(crate) fn combine(
+        &mut self,
+        dest_start: usize,
+        source: &PixelRow<T>,
+        source_range: Range<usize>,
+        mode: PlotMode,
+        write_mask: &WriteMask<T>,
+        key: Option<T>,
+    ) {
+        let source_end = source_range.end.min(source.width());
+        let length = source_end
+            .saturating_sub(source_range.start)
+            .min(self.width().saturating_sub(dest_start));
+        if length == 0 {
+            return;
+        }
+        // offset from a raw pixel in this row to the matching raw pixel in the source
+        let offset =
+            (source_range.start + source.pad_left) as isize - (dest_start + self.pad_left) as isize;
+        let pad_left = self.pad_left;
+        self.plot_chunks_with_masks(dest_start..dest_start + length, mode, |chunk_index| {
+            let chunk = source.chunk_at((chunk_index * T::pixels()) as isize + offset);
+            let mask = write_mask.chunk_mask(chunk_index, pad_left);
+            match key {
+                Some(key) => {
+                    let matched = (chunk & T::key_bits()).match_mask(key & T::key_bits());
+                    (chunk, mask & !matched)
+                }
+                None => (chunk, mask),
             }
+        });
+    }
+
+    // shift the pixels in `range` by `count` pixels, to the right for positive counts and left for negative
+    // pixels shifted out of the range are lost, and the exposed pixels are filled with `fill`
+    pub fn shift_range(&mut self, range: Range<usize>, count: isize, fill: T) {
+        let range = self.visible_range(range);
+        let shift = count.unsigned_abs();
+        if shift >= range.len() {
+            self.fill_range(range, fill);
+            return;
+        }
+        if count > 0 {
+            self.move_pixels(range.start + shift, range.start..range.end - shift);
+            self.fill_range(range.start..range.start + shift, fill);
+        } else if count < 0 {
+            self.move_pixels(range.start, range.start + shift..range.end);
+            self.fill_range(range.end - shift..range.end, fill);
+        }
+    }
+
+    pub fn shift(&mut self, count: isize, fill: T) {
+        self.shift_range(0..self.width(), count, fill);
+    }
+
+    // rotate the pixels in `range` by `count` pixels, to the right for positive counts and left for negative
+    // pixels shifted out of one end of the range wrap around to the other
+    pub fn rotate_range(&mut self, range: Range<usize>, count: isize) {
+        let range = self.visible_range(range);
+        if range.is_empty() {
+            return;
+        }
+        let shift = count.rem_euclid(range.len() as isize) as usize;
+        if shift == 0 {
+            return;
+        }
+        // only the pixels that wrap around need copying aside, so wrap whichever end is shorter
+        if shift <= range.len() / 2 {
+            let wrapped = self.visible_chunks_in(range.end - shift..range.end);
+            self.move_pixels(range.start + shift, range.start..range.end - shift);
+            self.fill_range_with_chunks(range.start..range.start + shift, &wrapped);
         } else {
-            // we can optimise our fill by using a filled chunk
-            self.fill_range_with_chunk(range, T::filled_pixel(pixel));
+            let shift = range.len() - shift;
+            let wrapped = self.visible_chunks_in(range.start..range.start + shift);
+            self.move_pixels(range.start, range.start + shift..range.end);
+            self.fill_range_with_chunks(range.end - shift..range.end, &wrapped);
         }
     }
 
-    pub fn fill_range_with_chunk(&mut self, range: Range<usize>, chunk: T) {
-        // fills a range with given chunk, where fill is aligned by chunk
-        // Work out how many pixels we need to skip at front to align with chunk
-        let skip_front = (range.start + self.pad_left) % T::pixels();
-        if skip_front > 0 {
-            // fill the front of the range
-            for i in range.start..range.start + skip_front {
-                let px = chunk.get_pixel(i % T::pixels()).unwrap();
-                self.set_pixel(i, px);
+    pub fn rotate(&mut self, count: isize) {
+        self.rotate_range(0..self.width(), count);
+    }
+
+    // mirror the pixels in `range`, so the first pixel becomes the last
+    pub fn reverse_range(&mut self, range: Range<usize>) {
+        let range = self.visible_range(range);
+        if range.len() < 2 {
+            return;
+        }
+        // only the chunks covering the range are copied aside
+        let raw_range = self.raw_range(range.clone());
+        let first_chunk = raw_range.start / T::pixels();
+        let source = self.pixel_chunks[first_chunk..raw_range.end.div_ceil(T::pixels())].to_vec();
+        // the raw pixel that each raw pixel in the range is swapped with is `mirror` minus its index
+        // so a destination chunk is the reverse of the chunk ending at that mirrored pixel
+        let mirror = (raw_range.start + raw_range.end - 1 - first_chunk * T::pixels()) as isize;
+        let full_mask = !T::default();
+        self.plot_chunks_with_masks(range, PlotMode::Set, |chunk_index| {
+            let offset = mirror - ((chunk_index + 1) * T::pixels()) as isize + 1;
+            (chunk_at(&source, offset).reverse_pixels(), full_mask)
+        });
+    }
+
+    pub fn reverse(&mut self) {
+        self.reverse_range(0..self.width());
+    }
+
+    // replace every pixel in `range` that matches `from` with `to`
+    pub fn replace_colour(&mut self, range: Range<usize>, from: T, to: T) {
+        self.write_replace_colour(range, from, to, &WriteMask::all());
+    }
+
+    pub(crate) fn write_replace_colour(
+        &mut self,
+        range: Range<usize>,
+        from: T,
+        to: T,
+        write_mask: &WriteMask<T>,
+    ) {
+        let to = T::filled_pixel(to);
+        self.map_chunks(range, write_mask, |chunk| {
+            let matched = chunk.match_mask(from);
+            (chunk & !matched) | (to & matched)
+        });
+    }
+
+    // alpha blend `colour` over a pixel
+    pub fn blend_pixel<M: ColourMap<T>>(&mut self, index: usize, colour: Colour, blend: &Blend<M>) {
+        self.blend_range_through(
+            index..index.saturating_add(1),
+            colour,
+            blend,
+            &WriteMask::all(),
+        );
+    }
+
+    // alpha blend `colour` over a range of pixels
+    pub fn blend_range<M: ColourMap<T>>(
+        &mut self,
+        range: Range<usize>,
+        colour: Colour,
+        blend: &Blend<M>,
+    ) {
+        self.blend_range_through(range, colour, blend, &WriteMask::all());
+    }
+
+    pub(crate) fn blend_range_through<M: ColourMap<T>>(
+        &mut self,
+        range: Range<usize>,
+        colour: Colour,
+        blend: &Blend<M>,
+        write_mask: &WriteMask<T>,
+    ) {
+        if blend.is_transparent(colour) {
+            return;
+        }
+        if blend.is_opaque(colour) {
+            let pixel = blend.map.pixel(Colour { a: 255, ..colour });
+            self.write_range(range, T::filled_pixel(pixel), PlotMode::Set, write_mask);
+            return;
+        }
+        // runs of the same destination pixel will blend to the same result
+        let mut last: Option<(T, T)> = None;
+        for i in self.visible_range(range) {
+            let dest = self.pixel(i).unwrap();
+            let pixel = match last {
+                Some((last_dest, last_pixel)) if last_dest == dest => last_pixel,
+                _ => blend.blend(colour, dest),
+            };
+            last = Some((dest, pixel));
+            self.write_pixel(i, pixel, PlotMode::Set, write_mask);
+        }
+    }
+
+    // alpha blend pixels from `source_range` in a source row with alpha over this row, starting at `dest_start`
+    // the blend is clipped to the width of both rows
+    pub fn blend_from<S, M>(
+        &mut self,
+        dest_start: usize,
+        source: &PixelRow<S>,
+        source_range: Range<usize>,
+        blend: &Blend<M>,
+    ) where
+        S: ColourPixel<PixelType = S>,
+        M: ColourMap<T>,
+    {
+        self.blend_from_through(dest_start, source, source_range, blend, &WriteMask::all());
+    }
+
+    pub(crate) fn blend_from_through<S, M>(
+        &mut self,
+        dest_start: usize,
+        source: &PixelRow<S>,
+        source_range: Range<usize>,
+        blend: &Blend<M>,
+        write_mask: &WriteMask<T>,
+    ) where
+        S: ColourPixel<PixelType = S>,
+        M: ColourMap<T>,
+    {
+        let source_end = source_range.end.min(source.width());
+        let length = source_end
+            .saturating_sub(source_range.start)
+            .min(self.width().saturating_sub(dest_start));
+        for i in 0..length {
+            let colour = source.pixel(source_range.start + i).unwrap().to_colour();
+            if blend.is_transparent(colour) {
+                continue;
             }
+            let dest = self.pixel(dest_start + i).unwrap();
+            let pixel = blend.blend(colour, dest);
+            self.write_pixel(dest_start + i, pixel, PlotMode::Set, write_mask);
         }
-        // Similarly work out what pixels at end need to be set to align with chunk
-        let skip_back = (self.pad_left + range.end) % T::pixels();
-        if skip_back > 0 {
-            // fill the back of the range
-            for i in range.end - skip_back..range.end {
-                let px = chunk.get_pixel(i % T::pixels()).unwrap();
-                self.set_pixel(i, px);
+    }
+
+    // gets a chunk made up of the pixels starting at raw pixel `offset` in the underlying chunks,
+    // ignoring padding.  pixels that lie outside of the chunks are returned as zero
+    fn chunk_at(&self, offset: isize) -> T {
+        chunk_at(&self.pixel_chunks, offset)
+    }
+
+    // gets a chunk made up of the visible pixels starting at `index`
+    // pixels outside of the visible width of the row are returned as zero
+    fn visible_chunk_at(&self, index: isize) -> T {
+        let from = (-index).clamp(0, T::pixels() as isize) as usize;
+        let to = (self.width() as isize - index).clamp(0, T::pixels() as isize) as usize;
+        self.chunk_at(index + self.pad_left as isize) & T::range_mask(from..to)
+    }
+
+    // copies the visible pixels in `range` into new chunks, starting at the first pixel of the first chunk
+    fn visible_chunks_in(&self, range: Range<usize>) -> Vec<T> {
+        range
+            .step_by(T::pixels())
+            .map(|index| self.visible_chunk_at(index as isize))
+            .collect()
+    }
+
+    // moves the visible pixels in `source_range` so they start at `dest_start`, in place
+    // chunks are visited working back from the direction of the move, so each source pixel
+    // is read before it can be overwritten
+    fn move_pixels(&mut self, dest_start: usize, source_range: Range<usize>) {
+        let offset = dest_start as isize - source_range.start as isize;
+        let spans = self.chunk_spans(dest_start..dest_start + source_range.len());
+        if spans.is_empty() {
+            return;
+        }
+        let chunks = self.chunks_mut();
+        let move_chunk = |(chunk_index, mask): (usize, T)| {
+            let source = chunk_at(chunks, (chunk_index * T::pixels()) as isize - offset);
+            chunks[chunk_index] = PlotMode::Set.apply_masked(chunks[chunk_index], source, mask);
+        };
+        if offset > 0 {
+            spans.rev().for_each(move_chunk);
+        } else {
+            spans.for_each(move_chunk);
+        }
+    }
+
+    // Applies `mode` to each chunk covering `range`
+    // `source` is called with the index of each chunk to get the chunk to combine with it
+    // Only bits allowed by `write_mask` are changed
+    fn plot_chunks<F>(
+        &mut self,
+        range: Range<usize>,
+        mode: PlotMode,
+        write_mask: &WriteMask<T>,
+        mut source: F,
+    ) where
+        F: FnMut(usize) -> T,
+    {
+        let pad_left = self.pad_left;
+        self.plot_chunks_with_masks(range, mode, |chunk_index| {
+            (
+                source(chunk_index),
+                write_mask.chunk_mask(chunk_index, pad_left),
+            )
+        });
+    }
+
+    // Applies `mode` to each chunk covering `range`
+    // `source` is called with the index of each chunk, and returns the chunk to combine with it
+    // along with a mask of the bits that may be changed
+    // Whole chunks are combined in one operation, with masks used for partial chunks at either end
+    // The row's storage is only touched if some chunk may change, so a shared row isn't copied
+    // for a write that is clipped or masked away
+    fn plot_chunks_with_masks<F>(&mut self, range: Range<usize>, mode: PlotMode, mut source: F)
+    where
+        F: FnMut(usize) -> (T, T),
+    {
+        let full_mask = !T::default();
+        let mut writes = self
+            .chunk_spans(range)
+            .map(|(chunk_index, range_mask)| {
+                let (chunk, write_mask) = source(chunk_index);
+                (chunk_index, chunk, range_mask & write_mask)
+            })
+            .filter(|&(_, _, mask)| mask != T::default())
+            .peekable();
+        if writes.peek().is_none() {
+            return;
+        }
+        let chunks = self.chunks_mut();
+        for (chunk_index, chunk, mask) in writes {
+            let dest = &mut chunks[chunk_index];
+            if mask == full_mask {
+                *dest = mode.apply(*dest, chunk);
+            } else {
+                *dest = mode.apply_masked(*dest, chunk, mask);
             }
         }
-        for i in range.start + skip_front..range.end - skip_back {
-            self.pixel_chunks[i / T::pixels()] = chunk;
+    }
+
+    // Replaces each chunk covering `range` with the result of calling `map` on it
+    // Only bits within the range, and allowed by `write_mask`, are changed
+    fn map_chunks<F>(&mut self, range: Range<usize>, write_mask: &WriteMask<T>, mut map: F)
+    where
+        F: FnMut(T) -> T,
+    {
+        let pad_left = self.pad_left;
+        let mut writes = self
+            .chunk_spans(range)
+            .map(|(chunk_index, range_mask)| {
+                (
+                    chunk_index,
+                    range_mask & write_mask.chunk_mask(chunk_index, pad_left),
+                )
+            })
+            .filter(|&(_, mask)| mask != T::default())
+            .peekable();
+        if writes.peek().is_none() {
+            return;
+        }
+        let chunks = self.chunks_mut();
+        for (chunk_index, mask) in writes {
+            let dest = &mut chunks[chunk_index];
+            *dest = (*dest & !mask) | (map(*dest) & mask);
         }
     }
 
-    pub fn fill_range_with(&mut self, range: Range<usize>, new_pixels: &[T]) {
-        // TODO think this through properly
-        // for now, whilst new_pixels is a slice of chunks, we'll treat each chunk as a single pixel
+    // the chunks covering `range`, clipped to the width of the row
+    fn chunk_spans(&self, range: Range<usize>) -> ChunkSpans<T> {
+        ChunkSpans::new(self.raw_range(range))
+    }
 
-        // // TODO this implementation feels wrong...  as it can't easily be optimized
-        // this _might_ be able to use copy_from_slice, combined with split_at_mut
-        for (i, pixel) in range.zip(new_pixels.iter()) {
+    // fill a range with pixels from a slice, where each entry is a single pixel value
+    // `fit` decides what happens when the slice length doesn't match the range
+    // entries with bits set beyond one pixel, such as a packed chunk, are rejected rather than misread
+    pub fn fill_range_with(
+        &mut self,
+        range: Range<usize>,
+        new_pixels: &[T::PixelType],
+        fit: SourceFit,
+    ) -> Result<(), Error> {
+        let mismatch = Error::SourceLength {
+            expected: range.len(),
+            actual: new_pixels.len(),
+        };
+        match fit {
+            SourceFit::Exact if new_pixels.len() != range.len() => return Err(mismatch),
+            SourceFit::Repeat if new_pixels.is_empty() && !range.is_empty() => {
+                return Err(mismatch)
+            }
+            _ => {}
+        }
+        if let Some(index) = new_pixels.iter().position(|pixel| !is_pixel_value(*pixel)) {
+            return Err(Error::InvalidPixel { index });
+        }
+        let pixels = new_pixels.iter().cycle().take(match fit {
+            SourceFit::Repeat => range.len(),
+            _ => new_pixels.len(),
+        });
+        for (i, pixel) in range.zip(pixels) {
             self.set_pixel(i, *pixel);
         }
+        Ok(())
+    }
+
+    // fill a range with pixels from a slice of packed chunks
+    // the first pixel of the first chunk is placed at the start of the range, shifting the
+    // source pixels as needed when the range doesn't start on a chunk boundary
+    // a short slice only fills the start of the range, and any extra pixels in a long slice are ignored
+    // returns the number of pixels written
+    pub fn fill_range_with_chunks(&mut self, range: Range<usize>, new_chunks: &[T]) -> usize {
+        self.write_range_with_chunks(range, new_chunks, PlotMode::Set, &WriteMask::all())
+    }
+
+    pub(crate) fn write_range_with_chunks(
+        &mut self,
+        range: Range<usize>,
+        new_chunks: &[T],
+        mode: PlotMode,
+        write_mask: &WriteMask<T>,
+    ) -> usize {
+        let range = self.visible_range(range);
+        let length = range.len().min(new_chunks.len() * T::pixels());
+        let offset = (range.start + self.pad_left) as isize;
+        self.plot_chunks(
+            range.start..range.start + length,
+            mode,
+            write_mask,
+            |chunk_index| chunk_at(new_chunks, (chunk_index * T::pixels()) as isize - offset),
+        );
+        length
+    }
+
+    // fill a range with pixels from a slice of packed chunks, where the range starts on a chunk boundary
+    // whole chunks are copied directly, with only a partial chunk at the end of the range being masked
+    // short and long slices are handled as with `fill_range_with_chunks`
+    // returns the number of pixels written, or `None` if the range is not aligned, leaving the row untouched
+    pub fn fill_range_with_aligned_chunks(
+        &mut self,
+        range: Range<usize>,
+        new_chunks: &[T],
+    ) -> Option<usize> {
+        let range = self.visible_range(range);
+        let start = range.start + self.pad_left;
+        #[allow(clippy::manual_is_multiple_of)]
+        if start % T::pixels() != 0 {
+            return None;
+        }
+        let length = range.len().min(new_chunks.len() * T::pixels());
+        if length == 0 {
+            return Some(0);
+        }
+        let first_chunk = start / T::pixels();
+        let whole_chunks = length / T::pixels();
+        let chunks = self.chunks_mut();
+        chunks[first_chunk..first_chunk + whole_chunks]
+            .copy_from_slice(&new_chunks[..whole_chunks]);
+        let remainder = length % T::pixels();
+        if remainder > 0 {
+            let dest = &mut chunks[first_chunk + whole_chunks];
+            *dest = PlotMode::Set.apply_masked(
+                *dest,
+                new_chunks[whole_chunks],
+                T::range_mask(0..remainder),
+            );
+        }
+        Some(length)
+    }
+
+    // iterate over the visible pixels of the row
+    pub fn iter(&self) -> PixelRowIterator<'_, T> {
+        self.iter_range(0..self.width())
+    }
+
+    // iterate over a range of the visible pixels of the row, clipped to the width of the row
+    pub fn iter_range(&self, range: Range<usize>) -> PixelRowIterator<'_, T> {
+        let range = self.raw_range(range);
+        PixelRowIterator {
+            row: self,
+            pixel_index: range.start,
+            end_index: range.end,
+        }
+    }
+
+    // iterate over the visible pixels of the row, allowing each to be read and written in place
+    pub fn iter_mut(&mut self) -> PixelRowIteratorMut<'_, T> {
+        self.iter_range_mut(0..self.width())
+    }
+
+    // iterate over a range of the visible pixels of the row, allowing each to be read and written in place
+    pub fn iter_range_mut(&mut self, range: Range<usize>) -> PixelRowIteratorMut<'_, T> {
+        let range = self.raw_range(range);
+        // an empty range needs no chunks, so a shared row isn't copied
+        let chunks: &mut [T] = if range.is_empty() {
+            &mut []
+        } else {
+            self.chunks_mut()
+        };
+        PixelRowIteratorMut {
+            chunks: Cell::from_mut(chunks).as_slice_of_cells(),
+            pixel_index: range.start,
+            end_index: range.end,
+        }
+    }
+
+    // find the first pixel in `range` that is `pixel`, searching in `direction`
+    // searches right from the start of the range, or left from the last pixel in it
+    pub fn find_pixel(&self, range: Range<usize>, direction: Direction, pixel: T) -> Option<usize> {
+        self.find(range, direction, |chunk| chunk.match_mask(pixel))
+    }
+
+    // find the first pixel in `range` that isn't `pixel`, searching as `find_pixel`
+    pub fn find_other_pixel(
+        &self,
+        range: Range<usize>,
+        direction: Direction,
+        pixel: T,
+    ) -> Option<usize> {
+        self.find(range, direction, |chunk| !chunk.match_mask(pixel))
+    }
+
+    // compares whole chunks with `matches`, which gives a mask of the matching pixels in a chunk
+    // and only looks at individual pixels in the chunk where a match is found
+    fn find<F>(&self, range: Range<usize>, direction: Direction, matches: F) -> Option<usize>
+    where
+        F: Fn(T) -> T,
+    {
+        let range = self.raw_range(range);
+        let found = |(chunk_index, span_mask): (usize, T)| {
+            let found = matches(self.pixel_chunks[chunk_index]) & span_mask;
+            if found == T::default() {
+                return None;
+            }
+            let mut pixels = (0..T::pixels()).filter(|&i| found & T::pixel_mask(i) != T::default());
+            let index = match direction {
+                Direction::Left => pixels.next_back(),
+                Direction::Right => pixels.next(),
+            }?;
+            Some(chunk_index * T::pixels() + index - self.pad_left)
+        };
+        let mut spans = ChunkSpans::<T>::new(range);
+        match direction {
+            Direction::Left => spans.rev().find_map(found),
+            Direction::Right => spans.find_map(found),
+        }
+    }
+
+    // the smallest range within `range` that holds every pixel that isn't `background`
+    // or `None` if every pixel in the range is `background`
+    pub fn bounds(&self, range: Range<usize>, background: T) -> Option<Range<usize>> {
+        let range = self.visible_range(range);
+        let start = self.find_other_pixel(range.clone(), Direction::Right, background)?;
+        let end = self.find_other_pixel(start..range.end, Direction::Left, background)?;
+        Some(start..end + 1)
+    }
+
+    // iterate over the chunks covering `range`, each with a mask of its bits that lie within the range
+    // the range is clipped to the width of the row
+    pub fn chunks_in(&self, range: Range<usize>) -> RowChunks<'_, T> {
+        RowChunks {
+            chunks: &self.pixel_chunks,
+            spans: self.chunk_spans(range),
+        }
+    }
+
+    // iterate over the chunks covering `range`, allowing each to be changed in place
+    pub fn chunks_in_mut(&mut self, range: Range<usize>) -> RowChunksMut<'_, T> {
+        let spans = self.chunk_spans(range);
+        let chunks = if spans.is_empty() {
+            [].iter_mut()
+        } else {
+            self.chunks_mut()[spans.next_chunk..spans.end_chunk].iter_mut()
+        };
+        RowChunksMut { chunks, spans }
+    }
+
+    // borrow a range of the row as a view of its own, clipped to the width of the row
+    pub fn slice(&self, range: Range<usize>) -> RowSlice<'_, T> {
+        RowSlice::new(self, range)
+    }
+
+    pub fn slice_mut(&mut self, range: Range<usize>) -> RowSliceMut<'_, T> {
+        RowSliceMut::new(self, range)
+    }
+
+    // change the width of the row, keeping its pixels aligned to `align`
+    // pixels that no longer fit are lost, and new pixels are set to `fill`
+    // the row is reallocated without any scroll padding
+    pub fn resize(&mut self, width: usize, align: Align, fill: T) {
+        let offset = align.offset(self.width(), width);
+        let mut row = PixelRow::new_with(width, T::filled_pixel(fill));
+        let source_start = (-offset).max(0) as usize;
+        row.copy_from(offset.max(0) as usize, self, source_start..self.width());
+        *self = row;
+    }
+
+    // checks that `index` is a visible pixel of the row
+    pub fn check_index(&self, index: usize) -> Result<(), Error> {
+        if index < self.width() {
+            Ok(())
+        } else {
+            Err(Error::OutOfBounds {
+                index,
+                length: self.width(),
+            })
+        }
+    }
+
+    // checks that `range` is in order and lies within the visible pixels of the row
+    pub fn check_range(&self, range: &Range<usize>) -> Result<(), Error> {
+        if range.start > range.end || range.end > self.width() {
+            Err(Error::InvalidRange {
+                start: range.start,
+                end: range.end,
+                length: self.width(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    // clips a range of visible pixels to the width of the row
+    fn visible_range(&self, range: Range<usize>) -> Range<usize> {
+        let end = range.end.min(self.width());
+        range.start.min(end)..end
     }
 
-    pub fn fill_range_with_chunks(&mut self, _range: Range<usize>, _new_chunks: &[T]) {
-        // TODO think this through properly
-        // what do we do about alignment?
-        // should we have two variants? one that aligns, and another that doesn't?
+    // converts a range of visible pixels into raw pixel indices, clipped to the width of the row
+    fn raw_range(&self, range: Range<usize>) -> Range<usize> {
+        let range = self.visible_range(range);
+        range.start + self.pad_left..range.end + self.pad_left
     }
 
     pub fn len(&self) -> usize {
@@ -149,24 +1390,451 @@ impl<T: PixelChunk<PixelType = T>> PixelRow<T> {
     }
 }
 
-impl<'a, T: PixelChunk> IntoIterator for &'a PixelRow<T> {
-    type Item = T;
-    type IntoIter = PixelRowIterator<'a, T>;
+// Checked versions of the row drawing operations, following the policy on `Error`
+impl<T: PixelChunk<PixelType = T>> PixelRow<T> {
+    pub fn try_set_pixel(&mut self, index: usize, pixel: T) -> Result<(), Error> {
+        self.check_index(index)?;
+        self.set_pixel(index, pixel);
+        Ok(())
+    }
+
+    pub fn try_fill_range(&mut self, range: Range<usize>, pixel: T) -> Result<(), Error> {
+        self.check_range(&range)?;
+        self.fill_range(range, pixel);
+        Ok(())
+    }
+
+    pub fn try_fill_range_with_chunk(
+        &mut self,
+        range: Range<usize>,
+        chunk: T,
+    ) -> Result<(), Error> {
+        self.check_range(&range)?;
+        self.fill_range_with_chunk(range, chunk);
+        Ok(())
+    }
+
+    pub fn try_set_pixel_masked(
+        &mut self,
+        index: usize,
+        pixel: T,
+        write_mask: T,
+    ) -> Result<(), Error> {
+        self.check_index(index)?;
+        self.set_pixel_masked(index, pixel, write_mask);
+        Ok(())
+    }
+
+    pub fn try_fill_range_masked(
+        &mut self,
+        range: Range<usize>,
+        pixel: T,
+        write_mask: T,
+    ) -> Result<(), Error> {
+        self.check_range(&range)?;
+        self.fill_range_masked(range, pixel, write_mask);
+        Ok(())
+    }
+
+    pub fn try_plot_pixel(&mut self, index: usize, pixel: T, mode: PlotMode) -> Result<(), Error> {
+        self.check_index(index)?;
+        self.plot_pixel(index, pixel, mode);
+        Ok(())
+    }
+
+    pub fn try_plot_pixel_masked(
+        &mut self,
+        index: usize,
+        pixel: T,
+        mode: PlotMode,
+        write_mask: T,
+    ) -> Result<(), Error> {
+        self.check_index(index)?;
+        self.plot_pixel_masked(index, pixel, mode, write_mask);
+        Ok(())
+    }
+
+    pub fn try_plot_range(
+        &mut self,
+        range: Range<usize>,
+        pixel: T,
+        mode: PlotMode,
+    ) -> Result<(), Error> {
+        self.check_range(&range)?;
+        self.plot_range(range, pixel, mode);
+        Ok(())
+    }
+
+    pub fn try_plot_range_masked(
+        &mut self,
+        range: Range<usize>,
+        pixel: T,
+        mode: PlotMode,
+        write_mask: T,
+    ) -> Result<(), Error> {
+        self.check_range(&range)?;
+        self.plot_range_masked(range, pixel, mode, write_mask);
+        Ok(())
+    }
+
+    // the range must also lie within the stencil
+    pub fn try_plot_range_stencilled(
+        &mut self,
+        range: Range<usize>,
+        pixel: T,
+        mode: PlotMode,
+        stencil: &PixelRow<Pixel1>,
+    ) -> Result<(), Error> {
+        self.check_range(&range)?;
+        stencil.check_range(&range)?;
+        self.plot_range_stencilled(range, pixel, mode, stencil);
+        Ok(())
+    }
+
+    pub fn try_plot_range_with_chunk(
+        &mut self,
+        range: Range<usize>,
+        chunk: T,
+        mode: PlotMode,
+    ) -> Result<(), Error> {
+        self.check_range(&range)?;
+        self.plot_range_with_chunk(range, chunk, mode);
+        Ok(())
+    }
+
+    pub fn try_plot_range_with_chunk_masked(
+        &mut self,
+        range: Range<usize>,
+        chunk: T,
+        mode: PlotMode,
+        write_mask: T,
+    ) -> Result<(), Error> {
+        self.check_range(&range)?;
+        self.plot_range_with_chunk_masked(range, chunk, mode, write_mask);
+        Ok(())
+    }
+
+    pub fn try_fill_range_with_pattern(
+        &mut self,
+        range: Range<usize>,
+        pattern: &Pattern<T>,
+        y: usize,
+    ) -> Result<(), Error> {
+        self.check_range(&range)?;
+        self.fill_range_with_pattern(range, pattern, y);
+        Ok(())
+    }
+
+    pub fn try_plot_range_with_pattern(
+        &mut self,
+        range: Range<usize>,
+        pattern: &Pattern<T>,
+        y: usize,
+        mode: PlotMode,
+    ) -> Result<(), Error> {
+        self.check_range(&range)?;
+        self.plot_range_with_pattern(range, pattern, y, mode);
+        Ok(())
+    }
+
+    pub fn try_plot_pixel_dotted(
+        &mut self,
+        index: usize,
+        pixel: T,
+        mode: PlotMode,
+        pattern: &mut LinePattern,
+    ) -> Result<(), Error> {
+        self.check_index(index)?;
+        self.plot_pixel_dotted(index, pixel, mode, pattern);
+        Ok(())
+    }
+
+    pub fn try_plot_range_dotted(
+        &mut self,
+        range: Range<usize>,
+        pixel: T,
+        mode: PlotMode,
+        pattern: &mut LinePattern,
+    ) -> Result<(), Error> {
+        self.check_range(&range)?;
+        self.plot_range_dotted(range, pixel, mode, pattern);
+        Ok(())
+    }
+
+    pub fn try_copy_from(
+        &mut self,
+        dest_start: usize,
+        source: &PixelRow<T>,
+        source_range: Range<usize>,
+    ) -> Result<(), Error> {
+        self.check_copy(dest_start, source, &source_range)?;
+        self.copy_from(dest_start, source, source_range);
+        Ok(())
+    }
+
+    pub fn try_combine_from(
+        &mut self,
+        dest_start: usize,
+        source: &PixelRow<T>,
+        source_range: Range<usize>,
+        mode: PlotMode,
+    ) -> Result<(), Error> {
+        self.check_copy(dest_start, source, &source_range)?;
+        self.combine_from(dest_start, source, source_range, mode);
+        Ok(())
+    }
+
+    pub fn try_combine_from_masked(
+        &mut self,
+        dest_start: usize,
+        source: &PixelRow<T>,
+        source_range: Range<usize>,
+        mode: PlotMode,
+        write_mask: T,
+    ) -> Result<(), Error> {
+        self.check_copy(dest_start, source, &source_range)?;
+        self.combine_from_masked(dest_start, source, source_range, mode, write_mask);
+        Ok(())
+    }
+
+    pub fn try_copy_from_keyed(
+        &mut self,
+        dest_start: usize,
+        source: &PixelRow<T>,
+        source_range: Range<usize>,
+        key: T,
+    ) -> Result<(), Error> {
+        self.check_copy(dest_start, source, &source_range)?;
+        self.copy_from_keyed(dest_start, source, source_range, key);
+        Ok(())
+    }
+
+    pub fn try_combine_from_keyed(
+        &mut self,
+        dest_start: usize,
+        source: &PixelRow<T>,
+        source_range: Range<usize>,
+        mode: PlotMode,
+        key: T,
+    ) -> Result<(), Error> {
+        self.check_copy(dest_start, source, &source_range)?;
+        self.combine_from_keyed(dest_start, source, source_range, mode, key);
+        Ok(())
+    }
+
+    pub fn try_shift_range(
+        &mut self,
+        range: Range<usize>,
+        count: isize,
+        fill: T,
+    ) -> Result<(), Error> {
+        self.check_range(&range)?;
+        self.shift_range(range, count, fill);
+        Ok(())
+    }
+
+    pub fn try_rotate_range(&mut self, range: Range<usize>, count: isize) -> Result<(), Error> {
+        self.check_range(&range)?;
+        self.rotate_range(range, count);
+        Ok(())
+    }
+
+    pub fn try_reverse_range(&mut self, range: Range<usize>) -> Result<(), Error> {
+        self.check_range(&range)?;
+        self.reverse_range(range);
+        Ok(())
+    }
+
+    pub fn try_replace_colour(&mut self, range: Range<usize>, from: T, to: T) -> Result<(), Error> {
+        self.check_range(&range)?;
+        self.replace_colour(range, from, to);
+        Ok(())
+    }
+
+    pub fn try_blend_pixel<M: ColourMap<T>>(
+        &mut self,
+        index: usize,
+        colour: Colour,
+        blend: &Blend<M>,
+    ) -> Result<(), Error> {
+        self.check_index(index)?;
+        self.blend_pixel(index, colour, blend);
+        Ok(())
+    }
+
+    pub fn try_blend_range<M: ColourMap<T>>(
+        &mut self,
+        range: Range<usize>,
+        colour: Colour,
+        blend: &Blend<M>,
+    ) -> Result<(), Error> {
+        self.check_range(&range)?;
+        self.blend_range(range, colour, blend);
+        Ok(())
+    }
+
+    pub fn try_blend_from<S, M>(
+        &mut self,
+        dest_start: usize,
+        source: &PixelRow<S>,
+        source_range: Range<usize>,
+        blend: &Blend<M>,
+    ) -> Result<(), Error>
+    where
+        S: ColourPixel<PixelType = S>,
+        M: ColourMap<T>,
+    {
+        self.check_copy(dest_start, source, &source_range)?;
+        self.blend_from(dest_start, source, source_range, blend);
+        Ok(())
+    }
+
+    // as `fill_range_with`, also returning an error if the range doesn't fit the row
+    pub fn try_fill_range_with(
+        &mut self,
+        range: Range<usize>,
+        new_pixels: &[T::PixelType],
+        fit: SourceFit,
+    ) -> Result<(), Error> {
+        self.check_range(&range)?;
+        self.fill_range_with(range, new_pixels, fit)
+    }
+
+    // the range must lie within the row, but the slice may be shorter or longer than it
+    // returns the number of pixels written
+    pub fn try_fill_range_with_chunks(
+        &mut self,
+        range: Range<usize>,
+        new_chunks: &[T],
+    ) -> Result<usize, Error> {
+        self.check_range(&range)?;
+        Ok(self.fill_range_with_chunks(range, new_chunks))
+    }
+
+    // as `try_fill_range_with_chunks`, also returning an error if the range isn't chunk aligned
+    pub fn try_fill_range_with_aligned_chunks(
+        &mut self,
+        range: Range<usize>,
+        new_chunks: &[T],
+    ) -> Result<usize, Error> {
+        self.check_range(&range)?;
+        let start = range.start;
+        self.fill_range_with_aligned_chunks(range, new_chunks)
+            .ok_or(Error::Unaligned { index: start })
+    }
+
+    // checks that `source_range` lies within `source`, and that it fits in this row at `dest_start`
+    fn check_copy<S: PixelChunk<PixelType = S>>(
+        &self,
+        dest_start: usize,
+        source: &PixelRow<S>,
+        source_range: &Range<usize>,
+    ) -> Result<(), Error> {
+        source.check_range(source_range)?;
+        self.check_range(&(dest_start..dest_start.saturating_add(source_range.len())))
+    }
+}
+
+impl<T: ByteChunk<PixelType = T>> PixelRow<T> {
+    // remap every pixel in `range` through a lookup table, a whole chunk at a time
+    pub fn remap_range(&mut self, range: Range<usize>, lut: &ColourLut<T>) {
+        self.write_remap_range(range, lut, &WriteMask::all());
+    }
+
+    pub fn try_remap_range(
+        &mut self,
+        range: Range<usize>,
+        lut: &ColourLut<T>,
+    ) -> Result<(), Error> {
+        self.check_range(&range)?;
+        self.remap_range(range, lut);
+        Ok(())
+    }
+
+    pub(crate) fn write_remap_range(
+        &mut self,
+        range: Range<usize>,
+        lut: &ColourLut<T>,
+        write_mask: &WriteMask<T>,
+    ) {
+        self.map_chunks(range, write_mask, |chunk| lut.map_chunk(chunk));
+    }
+}
+
+impl<T: PixelChunk<PixelType = T>> PixelRow<T> {
+    // the visible pixels of the row as whole chunks, starting at the first visible pixel
+    // with any pixels beyond the width of the row set to zero
+    pub(crate) fn visible_chunks(&self) -> impl Iterator<Item = T> + '_ {
+        (0..self.width())
+            .step_by(T::pixels())
+            .map(|index| self.visible_chunk_at(index as isize))
+    }
+}
+
+impl<T: PixelChunk<PixelType = T> + Eq + Hash> PixelRow<T> {
+    // count how many times each pixel value appears in `range`
+    pub fn histogram(&self, range: Range<usize>) -> HashMap<T, usize> {
+        let mut counts = HashMap::new();
+        self.add_to_histogram(range, &mut counts);
+        counts
+    }
+
+    // adds the pixel counts for `range` to `counts`
+    // chunks that are a single colour are counted in one go
+    pub(crate) fn add_to_histogram(&self, range: Range<usize>, counts: &mut HashMap<T, usize>) {
+        for (chunk, span_mask) in self.chunks_in(range) {
+            let first = chunk.get_pixel(0).unwrap_or_default();
+            if span_mask == !T::default() && chunk == T::filled_pixel(first) {
+                *counts.entry(first).or_insert(0) += T::pixels();
+                continue;
+            }
+            for index in 0..T::pixels() {
+                if span_mask & T::pixel_mask(index) != T::default() {
+                    let pixel = chunk.get_pixel(index).unwrap_or_default();
+                    *counts.entry(pixel).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+
+    // the distinct pixel values used in `range`
+    pub fn colours(&self, range: Range<usize>) -> HashSet<T> {
+        self.histogram(range).into_keys().collect()
+    }
+}
+
+impl<T: PixelChunk<PixelType = T>> PartialEq for PixelRow<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.width() == other.width() && self.visible_chunks().eq(other.visible_chunks())
+    }
+}
+
+impl<T: PixelChunk<PixelType = T> + Eq> Eq for PixelRow<T> {}
+
+impl<T: PixelChunk<PixelType = T> + Hash> Hash for PixelRow<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.width().hash(state);
+        for chunk in self.visible_chunks() {
+            chunk.hash(state);
+        }
+    }
+}
+
+impl<'a, T: PixelChunk<PixelType = T>> IntoIterator for &'a PixelRow<T> {
+    type Item = T;
+    type IntoIter = PixelRowIterator<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T: PixelChunk<PixelType = T>> IntoIterator for &'a mut PixelRow<T> {
+    type Item = PixelMut<'a, T>;
+    type IntoIter = PixelRowIteratorMut<'a, T>;
 
     fn into_iter(self) -> Self::IntoIter {
-        let mut iterator = PixelRowIterator {
-            row: self,
-            pixel_index: 0,
-            chunk_index: 0,
-            chunk_iterator: self.pixel_chunks[0].into_iter(),
-        };
-        // skip any padding set by pad_left
-        let mut counter = self.pad_left;
-        while counter > 0 {
-            iterator.next();
-            counter -= 1;
-        }
-        iterator
+        self.iter_mut()
     }
 }
 
@@ -183,7 +1851,7 @@ impl<T: PixelChunk> Index<usize> for PixelRow<T> {
 // NB this is fetching a pixel chunk...
 impl<T: PixelChunk> IndexMut<usize> for PixelRow<T> {
     fn index_mut(&mut self, index: usize) -> &mut T {
-        &mut self.pixel_chunks[index]
+        &mut self.chunks_mut()[index]
     }
 }
 
@@ -201,6 +1869,7 @@ impl<T: PixelChunk> Index<Range<usize>> for PixelRow<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::colour::{DirectColour, IndexedColour};
 
     #[test]
     fn can_create_pixel_row() {
@@ -209,7 +1878,7 @@ mod tests {
         for pixel in row.pixel_chunks.iter() {
             assert_eq!(pixel.value, 0);
         }
-        for pixel in &row.pixel_chunks {
+        for pixel in row.pixel_chunks.as_ref() {
             let test_px: u8 = (*pixel).into();
             assert_eq!(test_px, 0);
         }
@@ -307,6 +1976,336 @@ mod tests {
         assert_eq!(row.pad_right, 1);
     }
 
+    #[test]
+    fn can_fill_range_of_a_padded_pixelrow() {
+        let mut row: PixelRow<Pixel4> = PixelRow::new(10);
+        row.pad_left = 1;
+        row.pad_right = 1;
+        row.fill_range(0..8, 3.into());
+        assert_eq!(row.pixel_chunks[0].value, 0x03);
+        assert_eq!(row.pixel_chunks[1].value, 0x33);
+        assert_eq!(row.pixel_chunks[4].value, 0x30);
+
+        row.fill_range(3..4, 5.into());
+        assert_eq!(row.pixel(2), Some(3.into()));
+        assert_eq!(row.pixel(3), Some(5.into()));
+        assert_eq!(row.pixel(4), Some(3.into()));
+    }
+
+    #[test]
+    fn can_plot_pixel_with_modes() {
+        let mut row: PixelRow<Pixel4> = PixelRow::new_with(4, 0x55.into());
+        row.plot_pixel(0, 0xA.into(), PlotMode::Or);
+        row.plot_pixel(1, 0xC.into(), PlotMode::And);
+        row.plot_pixel(2, 0xF.into(), PlotMode::Xor);
+        row.plot_pixel(3, 0.into(), PlotMode::Invert);
+        assert_eq!(row.pixel(0), Some(0xF.into()));
+        assert_eq!(row.pixel(1), Some(0x4.into()));
+        assert_eq!(row.pixel(2), Some(0xA.into()));
+        assert_eq!(row.pixel(3), Some(0xA.into()));
+    }
+
+    #[test]
+    fn can_plot_range_with_modes() {
+        let mut row: PixelRow<Pixel4> = PixelRow::new(9);
+        for i in 0..9 {
+            row.set_pixel(i, (i as u8).into());
+        }
+
+        row.plot_range(1..8, 0x8.into(), PlotMode::Xor);
+        for i in 0..9 {
+            let expected = if (1..8).contains(&i) { i ^ 8 } else { i };
+            assert_eq!(row.pixel(i as usize), Some(expected.into()));
+        }
+
+        row.plot_range(2..9, 0.into(), PlotMode::Invert);
+        for i in 0..9 {
+            let mut expected = if (1..8).contains(&i) { i ^ 8 } else { i };
+            if i >= 2 {
+                expected = !expected & 0xF;
+            }
+            assert_eq!(row.pixel(i as usize), Some(expected.into()));
+        }
+        // padding pixel is untouched
+        assert_eq!(row.pixel_chunks[4].value & 0x0F, 0);
+
+        row.plot_range(0..9, 0x3.into(), PlotMode::And);
+        row.plot_range(0..3, 0x4.into(), PlotMode::Or);
+        assert_eq!(row.pixel(0), Some(0x4.into()));
+        assert_eq!(row.pixel(1), Some(0x5.into()));
+        assert_eq!(row.pixel(2), Some(0x5.into()));
+        assert_eq!(row.pixel(3), Some(0x0.into()));
+    }
+
+    #[test]
+    fn can_copy_between_rows_with_different_alignment() {
+        let mut source: PixelRow<Pixel4> = PixelRow::new(9);
+        for i in 0..9 {
+            source.set_pixel(i, (i as u8 + 1).into());
+        }
+
+        for dest_start in 0..4 {
+            for source_start in 0..3 {
+                let mut dest: PixelRow<Pixel4> = PixelRow::new(10);
+                dest.copy_from(dest_start, &source, source_start..source_start + 5);
+                for i in 0..10 {
+                    let expected = if (dest_start..dest_start + 5).contains(&i) {
+                        (i - dest_start + source_start + 1) as u8
+                    } else {
+                        0
+                    };
+                    assert_eq!(dest.pixel(i), Some(expected.into()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn can_copy_between_padded_rows() {
+        let mut source: PixelRow<Pixel4> = PixelRow::new(8);
+        source.pad_left = 1;
+        for i in 0..7 {
+            source.set_pixel(i, (i as u8 + 1).into());
+        }
+        let mut dest: PixelRow<Pixel4> = PixelRow::new_with(8, 0xFF.into());
+        dest.pad_left = 3;
+        dest.copy_from(0, &source, 0..7);
+        // clipped to the width of the destination
+        for i in 0..5 {
+            assert_eq!(dest.pixel(i), Some((i as u8 + 1).into()));
+        }
+        // pixels in the left padding are untouched
+        assert_eq!(dest.pixel_chunks[0].value, 0xFF);
+        assert_eq!(dest.pixel_chunks[1].value, 0xF1);
+    }
+
+    #[test]
+    fn can_combine_rows_with_modes() {
+        let source: PixelRow<Pixel4> = PixelRow::from_vec(vec![0x12.into(), 0x34.into()]);
+        let mut dest: PixelRow<Pixel4> = PixelRow::new_with(6, 0xFF.into());
+        dest.combine_from(1, &source, 0..4, PlotMode::And);
+        assert_eq!(dest.pixel_chunks[0].value, 0xF1);
+        assert_eq!(dest.pixel_chunks[1].value, 0x23);
+        assert_eq!(dest.pixel_chunks[2].value, 0x4F);
+
+        dest.combine_from(1, &source, 1..3, PlotMode::Xor);
+        assert_eq!(dest.pixel_chunks[0].value, 0xF3);
+        assert_eq!(dest.pixel_chunks[1].value, 0x13);
+
+        dest.combine_from(0, &source, 0..1, PlotMode::Not);
+        assert_eq!(dest.pixel(0), Some(0xE.into()));
+    }
+
+    #[test]
+    fn can_write_with_a_write_mask() {
+        let mut row: PixelRow<Pixel4> = PixelRow::new_with(7, 0x55.into());
+        // only update bit 3 of each pixel
+        row.set_pixel_masked(0, 0xF.into(), 0x8.into());
+        assert_eq!(row.pixel(0), Some(0xD.into()));
+        assert_eq!(row.pixel(1), Some(0x5.into()));
+
+        row.fill_range_masked(1..6, 0xA.into(), 0x8.into());
+        assert_eq!(row.pixel(0), Some(0xD.into()));
+        for i in 1..6 {
+            assert_eq!(row.pixel(i), Some(0xD.into()));
+        }
+        assert_eq!(row.pixel(6), Some(0x5.into()));
+
+        row.plot_range_masked(0..7, 0.into(), PlotMode::Invert, 0x3.into());
+        for i in 0..6 {
+            assert_eq!(row.pixel(i), Some(0xE.into()));
+        }
+        assert_eq!(row.pixel(6), Some(0x6.into()));
+
+        let source: PixelRow<Pixel4> = PixelRow::new_with(4, 0xFF.into());
+        row.combine_from_masked(2, &source, 0..3, PlotMode::Set, 0x1.into());
+        assert_eq!(row.pixel(1), Some(0xE.into()));
+        assert_eq!(row.pixel(2), Some(0xF.into()));
+        assert_eq!(row.pixel(4), Some(0xF.into()));
+        assert_eq!(row.pixel(5), Some(0xE.into()));
+    }
+
+    #[test]
+    fn can_copy_with_colour_key() {
+        let source: PixelRow<Pixel4> =
+            PixelRow::from_vec(vec![0x10.into(), 0x02.into(), 0x00.into(), 0x34.into()]);
+        let mut dest: PixelRow<Pixel4> = PixelRow::new_with(9, 0xFF.into());
+        dest.copy_from_keyed(1, &source, 0..8, 0.into());
+        let expected = [0xF, 0x1, 0xF, 0xF, 0x2, 0xF, 0xF, 0x3, 0x4];
+        for (i, value) in expected.iter().enumerate() {
+            assert_eq!(dest.pixel(i), Some((*value).into()));
+        }
+
+        let mut dest: PixelRow<Pixel4> = PixelRow::new_with(8, 0x11.into());
+        dest.combine_from_keyed(0, &source, 0..8, PlotMode::Xor, 3.into());
+        let expected = [0x0, 0x1, 0x1, 0x3, 0x1, 0x1, 0x1, 0x5];
+        for (i, value) in expected.iter().enumerate() {
+            assert_eq!(dest.pixel(i), Some((*value).into()));
+        }
+    }
+
+    #[test]
+    fn colour_key_ignores_alpha() {
+        // magenta is the key, whatever its alpha
+        let source: PixelRow<Argb8888> = PixelRow::from_vec(vec![
+            0xFFFF00FF.into(),
+            0x00FF00FF.into(),
+            0x8000FF00.into(),
+            0xFF00FF00.into(),
+        ]);
+        let mut dest: PixelRow<Argb8888> = PixelRow::new_with(4, 0xFF000000.into());
+        dest.copy_from_keyed(0, &source, 0..4, 0xFFFF00FF.into());
+        let pixels: Vec<u32> = dest.iter().map(|pixel| pixel.value).collect();
+        assert_eq!(pixels, [0xFF000000, 0xFF000000, 0x8000FF00, 0xFF00FF00]);
+
+        let source: PixelRow<Rgba2222> = PixelRow::from_vec(vec![
+            0b1100_0011.into(),
+            0b0000_0011.into(),
+            0b0000_1100.into(),
+        ]);
+        let mut dest: PixelRow<Rgba2222> = PixelRow::new(3);
+        dest.copy_from_keyed(0, &source, 0..3, 0b0100_0011.into());
+        let pixels: Vec<u8> = dest.iter().map(|pixel| pixel.value).collect();
+        assert_eq!(pixels, [0, 0, 0b0000_1100]);
+
+        let source: PixelRow<Argb4444> = PixelRow::from_vec(vec![0x0F0F.into(), 0xF0F0.into()]);
+        let mut dest: PixelRow<Argb4444> = PixelRow::new(2);
+        dest.copy_from_keyed(0, &source, 0..2, 0xFF0F.into());
+        let pixels: Vec<u16> = dest.iter().map(|pixel| pixel.value).collect();
+        assert_eq!(pixels, [0, 0xF0F0]);
+    }
+
+    #[test]
+    fn can_blend_into_direct_colour_row() {
+        let mut row: PixelRow<Argb8888> = PixelRow::new_with(4, 0xFF0000FF.into());
+        let blend = Blend::new(DirectColour);
+        row.blend_range(1..3, Colour::new(255, 0, 0, 128), &blend);
+        assert_eq!(row.pixel(0), Some(0xFF0000FF.into()));
+        assert_eq!(row.pixel(1), Some(0xFF80007F.into()));
+        assert_eq!(row.pixel(2), Some(0xFF80007F.into()));
+        assert_eq!(row.pixel(3), Some(0xFF0000FF.into()));
+
+        row.blend_pixel(3, Colour::opaque(0, 255, 0), &blend.with_global_alpha(0));
+        assert_eq!(row.pixel(3), Some(0xFF0000FF.into()));
+        row.blend_pixel(3, Colour::opaque(0, 255, 0), &blend);
+        assert_eq!(row.pixel(3), Some(0xFF00FF00.into()));
+    }
+
+    #[test]
+    fn can_blend_row_into_indexed_row() {
+        let palette = [
+            Colour::opaque(0, 0, 0),
+            Colour::opaque(255, 255, 255),
+            Colour::opaque(128, 128, 128),
+        ];
+        let blend = Blend::new(IndexedColour::new(&palette));
+        let source: PixelRow<Rgba2222> =
+            PixelRow::from_vec(vec![0x00.into(), 0xFF.into(), 0x7F.into()]);
+        let mut row: PixelRow<Pixel4> = PixelRow::new(5);
+        row.blend_from(1, &source, 0..3, &blend);
+        assert_eq!(row.pixel(0), Some(0.into()));
+        assert_eq!(row.pixel(1), Some(0.into()));
+        assert_eq!(row.pixel(2), Some(1.into()));
+        assert_eq!(row.pixel(3), Some(2.into()));
+        assert_eq!(row.pixel(4), Some(0.into()));
+    }
+
+    #[test]
+    fn can_plot_through_stencil() {
+        let mut stencil: PixelRow<Pixel1> = PixelRow::new(12);
+        stencil.fill_range(2..5, 1.into());
+        stencil.set_pixel(8, 1.into());
+        stencil.set_pixel(11, 1.into());
+
+        let mut row: PixelRow<Pixel4> = PixelRow::new(11);
+        row.pad_left = 1;
+        row.plot_range_stencilled(0..10, 7.into(), PlotMode::Set, &stencil);
+        for i in 0..10 {
+            let expected = if [2, 3, 4, 8].contains(&i) { 7 } else { 0 };
+            assert_eq!(row.pixel(i), Some(expected.into()));
+        }
+
+        // stencil pixels past the end of the stencil row are treated as clear
+        let mut row: PixelRow<Pixel8> = PixelRow::new(16);
+        let mut stencil: PixelRow<Pixel1> = PixelRow::new_with(12, 0xFF.into());
+        stencil.pad_left = 2;
+        row.plot_range_stencilled(0..16, 1.into(), PlotMode::Xor, &stencil);
+        for i in 0..16 {
+            let expected = if i < 10 { 1 } else { 0 };
+            assert_eq!(row.pixel(i), Some(expected.into()));
+        }
+    }
+
+    #[test]
+    fn can_fill_range_with_pattern() {
+        let pattern: Pattern<Pixel4> = Pattern::new(
+            3,
+            2,
+            &[1.into(), 2.into(), 3.into(), 4.into(), 5.into(), 6.into()],
+        );
+        let mut row: PixelRow<Pixel4> = PixelRow::new(12);
+        row.pad_left = 1;
+        row.fill_range_with_pattern(1..10, &pattern, 0);
+        assert_eq!(row.pixel(0), Some(0.into()));
+        for i in 1..10 {
+            assert_eq!(row.pixel(i), Some((i as u8 % 3 + 1).into()));
+        }
+        assert_eq!(row.pixel(10), Some(0.into()));
+
+        // pattern row is chosen by `y`
+        row.plot_range_with_pattern(0..11, &pattern, 3, PlotMode::Xor);
+        for i in 1..10 {
+            let expected = (i as u8 % 3 + 1) ^ (i as u8 % 3 + 4);
+            assert_eq!(row.pixel(i), Some(expected.into()));
+        }
+        assert_eq!(row.pixel(10), Some((10 % 3 + 4).into()));
+    }
+
+    #[test]
+    fn can_plot_dotted_ranges() {
+        let mut pattern = LinePattern::new(0b110 << 61, 3);
+        let mut row: PixelRow<Pixel4> = PixelRow::new(12);
+        row.plot_range_dotted(1..6, 5.into(), PlotMode::Set, &mut pattern);
+        // the pattern continues into the next span
+        row.plot_range_dotted(7..11, 7.into(), PlotMode::Set, &mut pattern);
+        row.plot_pixel_dotted(11, 9.into(), PlotMode::Set, &mut pattern);
+        let expected = [0, 5, 5, 0, 5, 5, 0, 0, 7, 7, 0, 9];
+        for (i, value) in expected.iter().enumerate() {
+            assert_eq!(row.pixel(i), Some((*value).into()));
+        }
+
+        pattern.restart();
+        let mut row: PixelRow<Pixel1> = PixelRow::new(20);
+        row.plot_range_dotted(2..20, 1.into(), PlotMode::Set, &mut pattern);
+        for i in 0..20 {
+            let expected = if i >= 2 && (i - 2) % 3 != 2 { 1 } else { 0 };
+            assert_eq!(row.pixel(i), Some(expected.into()));
+        }
+    }
+
+    #[test]
+    fn can_replace_colours() {
+        let mut row: PixelRow<Pixel2> = PixelRow::new(11);
+        for i in 0..11 {
+            row.set_pixel(i, (i as u8 % 4).into());
+        }
+        row.replace_colour(1..10, 2.into(), 3.into());
+        for i in 0..11 {
+            let expected = match i % 4 {
+                2 if (1..10).contains(&i) => 3,
+                value => value as u8,
+            };
+            assert_eq!(row.pixel(i), Some(expected.into()));
+        }
+
+        let lut = ColourLut::from_table(&[1.into(), 2.into(), 3.into(), 0.into()]);
+        row.remap_range(0..5, &lut);
+        let expected = [1, 2, 0, 0, 1, 1, 3, 3, 0, 1, 2];
+        for (i, value) in expected.iter().enumerate() {
+            assert_eq!(row.pixel(i), Some((*value).into()));
+        }
+    }
+
     #[test]
     fn can_get_width_of_pixelrow() {
         let row: PixelRow<Pixel8> = PixelRow::new(3);
@@ -321,6 +2320,11 @@ mod tests {
         row.pad_left = 1;
         assert_eq!(row.width(), 6);
         assert_eq!(row.pixel_chunks.len(), 4);
+
+        let row: PixelRow<Pixel1> = PixelRow::new(13);
+        assert_eq!(row.width(), 13);
+        assert_eq!(row.pad_right, 3);
+        assert_eq!(row.pixel_chunks.len(), 2);
     }
 
     #[test]
@@ -359,22 +2363,140 @@ mod tests {
     fn can_fill_range_with_multiple_pixels() {
         let mut row: PixelRow<Pixel8> =
             PixelRow::from_vec(vec![0.into(), 1.into(), 2.into(), 3.into(), 4.into()]);
-        row.fill_range_with(1..4, &[5.into(), 6.into(), 7.into()]);
+        row.fill_range_with(1..4, &[5.into(), 6.into(), 7.into()], SourceFit::Exact)
+            .unwrap();
         assert_eq!(row.pixel(0), Some(0.into()));
         assert_eq!(row.pixel(1), Some(5.into()));
         assert_eq!(row.pixel(2), Some(6.into()));
         assert_eq!(row.pixel(3), Some(7.into()));
         assert_eq!(row.pixel(4), Some(4.into()));
+    }
+
+    #[test]
+    fn can_fill_range_with_short_and_long_slices() {
+        let new_pixels: [Pixel4; 2] = [5.into(), 6.into()];
+
+        // short slices can be repeated...
+        let mut row: PixelRow<Pixel4> = PixelRow::new(7);
+        row.fill_range_with(1..6, &new_pixels, SourceFit::Repeat)
+            .unwrap();
+        let expected = [0, 5, 6, 5, 6, 5, 0];
+        for (i, value) in expected.iter().enumerate() {
+            assert_eq!(row.pixel(i), Some((*value).into()));
+        }
+
+        // ...truncated...
+        let mut row: PixelRow<Pixel4> = PixelRow::new(7);
+        row.fill_range_with(1..6, &new_pixels, SourceFit::Truncate)
+            .unwrap();
+        let expected = [0, 5, 6, 0, 0, 0, 0];
+        for (i, value) in expected.iter().enumerate() {
+            assert_eq!(row.pixel(i), Some((*value).into()));
+        }
+
+        // ...or rejected
+        let mut row: PixelRow<Pixel4> = PixelRow::new(7);
+        assert_eq!(
+            row.fill_range_with(1..6, &new_pixels, SourceFit::Exact),
+            Err(Error::SourceLength {
+                expected: 5,
+                actual: 2
+            })
+        );
+        assert_eq!(row.pixel(1), Some(0.into()));
+
+        // long slices are truncated unless an exact fit is needed
+        let mut row: PixelRow<Pixel4> = PixelRow::new(7);
+        row.fill_range_with(5..6, &new_pixels, SourceFit::Repeat)
+            .unwrap();
+        row.fill_range_with(0..1, &new_pixels, SourceFit::Truncate)
+            .unwrap();
+        assert!(row
+            .fill_range_with(2..3, &new_pixels, SourceFit::Exact)
+            .is_err());
+        let expected = [5, 0, 0, 0, 0, 5, 0];
+        for (i, value) in expected.iter().enumerate() {
+            assert_eq!(row.pixel(i), Some((*value).into()));
+        }
+
+        // each entry must be a single pixel value, so packed chunks aren't misread
+        let mut row: PixelRow<Pixel4> = PixelRow::new(2);
+        assert_eq!(
+            row.fill_range_with(0..2, &[0xB.into(), 0xCD.into()], SourceFit::Exact),
+            Err(Error::InvalidPixel { index: 1 })
+        );
+        assert_eq!(row.pixel_chunks[0].value, 0);
+        row.fill_range_with(0..2, &[0xB.into(), 0xD.into()], SourceFit::Exact)
+            .unwrap();
+        assert_eq!(row.pixel_chunks[0].value, 0xBD);
+        let mut row: PixelRow<Pixel1> = PixelRow::new(3);
+        assert!(row
+            .fill_range_with(0..3, &[1.into(), 2.into()], SourceFit::Repeat)
+            .is_err());
+
+        // an empty slice can't be repeated
+        assert!(row.fill_range_with(0..2, &[], SourceFit::Repeat).is_err());
+        assert!(row.fill_range_with(0..0, &[], SourceFit::Repeat).is_ok());
+    }
+
+    #[test]
+    fn can_fill_range_with_unaligned_chunks() {
+        let chunks: [Pixel4; 3] = [0x12.into(), 0x34.into(), 0x56.into()];
+        for start in 0..4 {
+            let mut row: PixelRow<Pixel4> = PixelRow::new_with(12, 0xFF.into());
+            row.pad_left = 1;
+            assert_eq!(row.fill_range_with_chunks(start..start + 5, &chunks), 5);
+            for i in 0..11 {
+                let expected = if (start..start + 5).contains(&i) {
+                    (i - start + 1) as u8
+                } else {
+                    0xF
+                };
+                assert_eq!(row.pixel(i), Some(expected.into()));
+            }
+        }
+
+        // a short slice only fills the start of the range
+        let mut row: PixelRow<Pixel4> = PixelRow::new(10);
+        assert_eq!(row.fill_range_with_chunks(1..10, &chunks[..2]), 4);
+        assert_eq!(row.pixel(4), Some(4.into()));
+        assert_eq!(row.pixel(5), Some(0.into()));
+
+        // and a long slice is truncated to the range
+        let mut row: PixelRow<Pixel4> = PixelRow::new(10);
+        assert_eq!(row.fill_range_with_chunks(3..6, &chunks), 3);
+        assert_eq!(row.pixel(2), Some(0.into()));
+        assert_eq!(row.pixel(5), Some(3.into()));
+        assert_eq!(row.pixel(6), Some(0.into()));
+    }
+
+    #[test]
+    fn can_fill_range_with_aligned_chunks() {
+        let chunks: [Pixel2; 2] = [0b00_01_10_11.into(), 0b11_10_01_00.into()];
+        let mut row: PixelRow<Pixel2> = PixelRow::new(14);
+        row.pad_left = 2;
+        assert_eq!(row.fill_range_with_aligned_chunks(1..8, &chunks), None);
+        assert_eq!(row.fill_range_with_aligned_chunks(2..8, &chunks), Some(6));
+        let expected = [0, 0, 0, 1, 2, 3, 3, 2, 0, 0, 0, 0];
+        for (i, value) in expected.iter().enumerate() {
+            assert_eq!(row.pixel(i), Some((*value).into()));
+        }
+
+        // short slice
+        let mut row: PixelRow<Pixel2> = PixelRow::new(16);
+        assert_eq!(row.fill_range_with_aligned_chunks(4..16, &chunks), Some(8));
+        assert_eq!(row.pixel(11), Some(0.into()));
+        assert_eq!(row.pixel(10), Some(1.into()));
+        assert_eq!(row.pixel_chunks[3].value, 0);
 
-        // TODO test with pixels slice that's short, long, etc
-        // and define what that behaviour should be
-        // let mut row: PixelRow<u8> = PixelRow::from_vec(vec![0, 1, 2, 3, 4]);
-        // row.fill_range_with(1..4, &[5, 6]);
-        // assert_eq!(row.pixel_chunks[0], 0);
-        // assert_eq!(row.pixel_chunks[1], 5);
-        // assert_eq!(row.pixel_chunks[2], 6);
-        // assert_eq!(row.pixel_chunks[3], 3);
-        // assert_eq!(row.pixel_chunks[4], 4);
+        // long slice
+        let mut row: PixelRow<Pixel2> = PixelRow::new(16);
+        assert_eq!(row.fill_range_with_aligned_chunks(0..1, &chunks), Some(1));
+        assert_eq!(row.pixel_chunks[0].value, 0);
+        let mut row: PixelRow<Pixel2> = PixelRow::new(16);
+        assert_eq!(row.fill_range_with_aligned_chunks(0..5, &chunks), Some(5));
+        assert_eq!(row.pixel_chunks[0], chunks[0]);
+        assert_eq!(row.pixel_chunks[1].value, 0b11_00_00_00);
     }
 
     #[test]
@@ -394,6 +2516,521 @@ mod tests {
         assert_eq!(iter.next(), None);
     }
 
+    #[test]
+    fn can_scroll_row() {
+        let mut row: PixelRow<Pixel4> = PixelRow::new_scrollable(3, 7);
+        assert_eq!(row.width(), 3);
+        assert_eq!(row.stored_width(), 8);
+        assert_eq!(row.max_scroll_offset(), 5);
+        for i in 0..3 {
+            row.set_pixel(i, (i as u8 + 1).into());
+        }
+        row.set_scroll_offset(1);
+        assert_eq!(row.width(), 3);
+        assert_eq!(row.pixel(0), Some(2.into()));
+        row.fill_range(2..3, 9.into());
+        assert_eq!(row[1].value, 0x39);
+
+        row.scroll_by(10);
+        assert_eq!(row.scroll_offset(), 5);
+        assert_eq!(row.width(), 3);
+        row.scroll_by(-4);
+        assert_eq!(row.scroll_offset(), 1);
+        row.scroll_by(-4);
+        assert_eq!(row.scroll_offset(), 0);
+        let pixels: Vec<u8> = row.iter().map(|pixel| pixel.value).collect();
+        assert_eq!(pixels, [1, 2, 3]);
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn clips_or_rejects_out_of_bounds_writes() {
+        let mut row: PixelRow<Pixel4> = PixelRow::new(5);
+        row.set_pixel(5, 1.into());
+        row.set_pixel(usize::MAX, 1.into());
+        row.fill_range(3..100, 2.into());
+        row.fill_range(4..1, 3.into());
+        row.plot_range_dotted(4..9, 5.into(), PlotMode::Set, &mut LinePattern::new(!0, 64));
+        row.blend_pixel(
+            usize::MAX,
+            Colour::new(0, 0, 0, 128),
+            &Blend::new(IndexedColour { palette: &[] }),
+        );
+        assert_eq!(row.fill_range_with_chunks(3..10, &[0x77.into(); 4]), 2);
+        assert_eq!(row.pixel(5), None);
+        // padding is untouched
+        assert_eq!(row[2].value, 0x70);
+
+        assert_eq!(
+            row.try_set_pixel(5, 1.into()),
+            Err(Error::OutOfBounds {
+                index: 5,
+                length: 5
+            })
+        );
+        assert_eq!(
+            row.try_fill_range(4..1, 1.into()),
+            Err(Error::InvalidRange {
+                start: 4,
+                end: 1,
+                length: 5
+            })
+        );
+        assert!(row.try_plot_range(0..6, 1.into(), PlotMode::Xor).is_err());
+        assert_eq!(row.pixel(0), Some(0.into()));
+        assert_eq!(row.try_fill_range(0..5, 1.into()), Ok(()));
+        assert_eq!(row.pixel(4), Some(1.into()));
+
+        let source = row.clone();
+        assert!(row.try_copy_from(3, &source, 0..3).is_err());
+        assert!(row.try_copy_from(0, &source, 3..6).is_err());
+        assert_eq!(row.try_copy_from(2, &source, 0..3), Ok(()));
+    }
+
+    #[test]
+    fn checked_variants_leave_row_untouched() {
+        let mut row: PixelRow<Pixel4> = PixelRow::new(5);
+        for i in 0..5 {
+            row.set_pixel(i, (i as u8).into());
+        }
+        let before = row.clone();
+        let mut pattern = LinePattern::new(0b1010, 4);
+        assert!(row
+            .try_plot_range_dotted(3..6, 9.into(), PlotMode::Set, &mut pattern)
+            .is_err());
+        assert_eq!(pattern.phase(), 0);
+        assert!(row.try_shift_range(0..6, 1, 0.into()).is_err());
+        assert!(row.try_rotate_range(2..9, 1).is_err());
+        assert!(row.try_reverse_range(0..6).is_err());
+        assert!(row.try_replace_colour(4..6, 4.into(), 0.into()).is_err());
+        assert!(row
+            .try_plot_range_stencilled(0..5, 9.into(), PlotMode::Set, &PixelRow::new(4))
+            .is_err());
+        assert!(row.try_copy_from_keyed(3, &before, 0..3, 0.into()).is_err());
+        assert!(row
+            .try_fill_range_with_chunks(4..6, &[0x99.into()])
+            .is_err());
+        assert!(row == before);
+
+        assert_eq!(
+            row.try_fill_range_with_aligned_chunks(1..5, &[0x99.into(); 2]),
+            Err(Error::Unaligned { index: 1 })
+        );
+        assert_eq!(
+            row.try_fill_range_with_aligned_chunks(2..5, &[0x99.into(); 2]),
+            Ok(3)
+        );
+        assert_eq!(row.try_reverse_range(0..5), Ok(()));
+        assert_eq!(row.pixel(0), Some(9.into()));
+        assert_eq!(row.pixel(4), Some(0.into()));
+    }
+
+    #[test]
+    fn can_resize_row() {
+        let mut row: PixelRow<Pixel4> = PixelRow::new(4);
+        row.pad_left = 1;
+        row.pad_right = 1;
+        row.set_pixel(0, 1.into());
+        row.set_pixel(1, 2.into());
+        row.resize(5, Align::End, 7.into());
+        let pixels: Vec<u8> = row.iter().map(|pixel| pixel.value).collect();
+        assert_eq!(pixels, [7, 7, 7, 1, 2]);
+        assert_eq!(row.len(), 3);
+        assert_eq!(row.scroll_offset(), 0);
+
+        row.resize(8, Align::Centre, 9.into());
+        let pixels: Vec<u8> = row.iter().map(|pixel| pixel.value).collect();
+        assert_eq!(pixels, [9, 7, 7, 7, 1, 2, 9, 9]);
+        row.resize(3, Align::Centre, 0.into());
+        let pixels: Vec<u8> = row.iter().map(|pixel| pixel.value).collect();
+        assert_eq!(pixels, [7, 7, 1]);
+        row.resize(2, Align::Start, 0.into());
+        let pixels: Vec<u8> = row.iter().map(|pixel| pixel.value).collect();
+        assert_eq!(pixels, [7, 7]);
+        row.resize(0, Align::End, 0.into());
+        assert!(row.is_empty());
+    }
+
+    #[test]
+    fn can_count_colours_in_row() {
+        let mut row: PixelRow<Pixel4> = PixelRow::new(12);
+        row.pad_left = 1;
+        row.pad_right = 1;
+        row.fill_range(0..10, 3.into());
+        row.set_pixel(4, 5.into());
+        row.set_pixel(9, 0.into());
+        let histogram = row.histogram(0..10);
+        assert_eq!(histogram.len(), 3);
+        assert_eq!(histogram[&3.into()], 8);
+        assert_eq!(histogram[&5.into()], 1);
+        assert_eq!(histogram[&0.into()], 1);
+        assert_eq!(row.histogram(2..4).len(), 1);
+        assert_eq!(row.histogram(2..4)[&3.into()], 2);
+        assert_eq!(
+            row.colours(0..20),
+            HashSet::from([0.into(), 3.into(), 5.into()])
+        );
+        assert!(row.histogram(10..20).is_empty());
+    }
+
+    #[test]
+    fn can_find_bounds_in_row() {
+        let mut row: PixelRow<Pixel1> = PixelRow::new(20);
+        assert_eq!(row.bounds(0..20, 0.into()), None);
+        row.set_pixel(3, 1.into());
+        row.set_pixel(12, 1.into());
+        assert_eq!(row.bounds(0..20, 0.into()), Some(3..13));
+        assert_eq!(row.bounds(4..20, 0.into()), Some(12..13));
+        assert_eq!(row.bounds(0..20, 1.into()), Some(0..20));
+        assert_eq!(row.bounds(4..12, 0.into()), None);
+    }
+
+    #[test]
+    fn can_iterate_over_chunks_in_range() {
+        let mut row: PixelRow<Pixel4> = PixelRow::new(10);
+        row.pad_left = 1;
+        row.pad_right = 1;
+        row.fill_range(0..8, 5.into());
+        let chunks: Vec<(u8, u8)> = row
+            .chunks_in(2..6)
+            .map(|(chunk, mask)| (chunk.value, mask.value))
+            .collect();
+        assert_eq!(chunks, [(0x55, 0x0F), (0x55, 0xFF), (0x55, 0xF0)]);
+        assert_eq!(row.chunks_in(0..8).len(), 5);
+        assert_eq!(row.chunks_in(0..8).next_back().unwrap().1.value, 0xF0);
+        assert_eq!(row.chunks_in(0..100).len(), 5);
+        assert_eq!(row.chunks_in(8..100).len(), 0);
+
+        for (chunk, mask) in row.chunks_in_mut(1..6) {
+            *chunk = PlotMode::Set.apply_masked(*chunk, 0x99.into(), mask);
+        }
+        let pixels: Vec<u8> = row.iter().map(|pixel| pixel.value).collect();
+        assert_eq!(pixels, [5, 9, 9, 9, 9, 9, 5, 5]);
+        assert_eq!(row[0].value, 0x05);
+        let (last, _) = row.chunks_in_mut(0..8).next_back().unwrap();
+        *last = 0xAA.into();
+        assert_eq!(row[4].value, 0xAA);
+    }
+
+    #[test]
+    fn can_find_pixels_in_row() {
+        let mut row: PixelRow<Pixel2> = PixelRow::new(22);
+        row.pad_left = 1;
+        row.pad_right = 1;
+        row.fill_range(5..15, 2.into());
+        row.set_pixel(9, 1.into());
+        assert_eq!(row.find_pixel(0..20, Direction::Right, 2.into()), Some(5));
+        assert_eq!(row.find_pixel(0..20, Direction::Left, 2.into()), Some(14));
+        assert_eq!(
+            row.find_other_pixel(6..20, Direction::Right, 2.into()),
+            Some(9)
+        );
+        assert_eq!(
+            row.find_other_pixel(0..9, Direction::Left, 2.into()),
+            Some(4)
+        );
+        assert_eq!(
+            row.find_other_pixel(10..20, Direction::Right, 2.into()),
+            Some(15)
+        );
+        assert_eq!(
+            row.find_other_pixel(10..15, Direction::Left, 2.into()),
+            None
+        );
+
+        // the end of the range is excluded, and the start included, in both directions
+        assert_eq!(row.find_pixel(0..5, Direction::Right, 2.into()), None);
+        assert_eq!(row.find_pixel(0..5, Direction::Left, 2.into()), None);
+        assert_eq!(row.find_pixel(5..6, Direction::Right, 2.into()), Some(5));
+        assert_eq!(row.find_pixel(5..6, Direction::Left, 2.into()), Some(5));
+        assert_eq!(row.find_pixel(15..20, Direction::Left, 2.into()), None);
+        assert_eq!(row.find_pixel(14..20, Direction::Left, 2.into()), Some(14));
+        assert_eq!(row.find_pixel(14..20, Direction::Right, 2.into()), Some(14));
+
+        // searches are clipped to the row
+        assert_eq!(
+            row.find_other_pixel(0..51, Direction::Left, 0.into()),
+            Some(14)
+        );
+        assert_eq!(row.find_pixel(16..50, Direction::Right, 1.into()), None);
+
+        let row: PixelRow<Pixel8> = PixelRow::new_with(4, 3.into());
+        assert_eq!(row.find_pixel(0..3, Direction::Left, 3.into()), Some(2));
+        assert_eq!(row.find_other_pixel(0..4, Direction::Right, 3.into()), None);
+    }
+
+    #[test]
+    fn can_shift_row() {
+        let mut row: PixelRow<Pixel4> = PixelRow::new(12);
+        row.pad_left = 1;
+        row.pad_right = 1;
+        for i in 0..10 {
+            row.set_pixel(i, (i as u8).into());
+        }
+        row.shift_range(2..8, 3, 15.into());
+        let pixels: Vec<u8> = row.iter().map(|pixel| pixel.value).collect();
+        assert_eq!(pixels, [0, 1, 15, 15, 15, 2, 3, 4, 8, 9]);
+        row.shift(-2, 14.into());
+        let pixels: Vec<u8> = row.iter().map(|pixel| pixel.value).collect();
+        assert_eq!(pixels, [15, 15, 15, 2, 3, 4, 8, 9, 14, 14]);
+        row.shift_range(8..20, 5, 0.into());
+        let pixels: Vec<u8> = row.iter().map(|pixel| pixel.value).collect();
+        assert_eq!(pixels, [15, 15, 15, 2, 3, 4, 8, 9, 0, 0]);
+    }
+
+    #[test]
+    fn can_rotate_row() {
+        let mut row: PixelRow<Pixel1> = PixelRow::new(16);
+        row[0] = Pixel1 { value: 0b1100_0001 };
+        row[1] = Pixel1 { value: 0b1000_0001 };
+        row.rotate(3);
+        assert_eq!(row[0].value, 0b0011_1000);
+        assert_eq!(row[1].value, 0b0011_0000);
+        row.rotate(-19);
+        assert_eq!(row[0].value, 0b1100_0001);
+        assert_eq!(row[1].value, 0b1000_0001);
+        row.rotate_range(4..12, -2);
+        assert_eq!(row[0].value, 0b1100_0110);
+        assert_eq!(row[1].value, 0b0000_0001);
+    }
+
+    #[test]
+    fn shifts_and_rotates_in_place() {
+        let pixels = |row: &PixelRow<Pixel2>| -> Vec<u8> { row.iter().map(|p| p.value).collect() };
+        for start in 0..6 {
+            for end in start..14 {
+                for count in -9isize..10 {
+                    let mut row: PixelRow<Pixel2> = PixelRow::new(16);
+                    row.pad_left = 3;
+                    for i in 0..13 {
+                        row.set_pixel(i, ((i % 3) as u8 + 1).into());
+                    }
+                    let storage = Arc::as_ptr(&row.pixel_chunks);
+                    let mut expected = pixels(&row);
+                    expected[start..end]
+                        .rotate_right(count.rem_euclid((end - start).max(1) as isize) as usize);
+                    row.rotate_range(start..end, count);
+                    assert_eq!(pixels(&row), expected);
+
+                    let mut expected = pixels(&row);
+                    let shift = count.unsigned_abs().min(end - start);
+                    if count > 0 {
+                        expected.copy_within(start..end - shift, start + shift);
+                        expected[start..start + shift].fill(0);
+                    } else {
+                        expected.copy_within(start + shift..end, start);
+                        expected[end - shift..end].fill(0);
+                    }
+                    row.shift_range(start..end, count, 0.into());
+                    assert_eq!(pixels(&row), expected);
+                    // the row's storage is changed without being copied
+                    assert_eq!(Arc::as_ptr(&row.pixel_chunks), storage);
+                    // padding is left alone
+                    assert_eq!(row[0].value & 0b1111_1100, 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn can_reverse_row() {
+        let mut row: PixelRow<Pixel4> = PixelRow::new(12);
+        row.pad_left = 1;
+        row.pad_right = 2;
+        for i in 0..9 {
+            row.set_pixel(i, (i as u8 + 1).into());
+        }
+        row.reverse();
+        let pixels: Vec<u8> = row.iter().map(|pixel| pixel.value).collect();
+        assert_eq!(pixels, [9, 8, 7, 6, 5, 4, 3, 2, 1]);
+        row.reverse_range(2..7);
+        let pixels: Vec<u8> = row.iter().map(|pixel| pixel.value).collect();
+        assert_eq!(pixels, [9, 8, 3, 4, 5, 6, 7, 2, 1]);
+        // padding is left alone
+        assert_eq!(row[0].value >> 4, 0);
+        assert_eq!(row[5].value, 0);
+
+        let mut row: PixelRow<Pixel1> = PixelRow::new(20);
+        row[0] = Pixel1 { value: 0b1110_0000 };
+        row[1] = Pixel1 { value: 0b0000_0001 };
+        row.reverse();
+        assert_eq!(row[0].value, 0b0000_1000);
+        assert_eq!(row[1].value, 0b0000_0000);
+        assert_eq!(row[2].value, 0b0111_0000);
+
+        // every alignment of range, reversed in place
+        for start in 0..6 {
+            for end in start..14 {
+                let mut row: PixelRow<Pixel2> = PixelRow::new(16);
+                row.pad_left = 3;
+                for i in 0..13 {
+                    row.set_pixel(i, ((i % 3) as u8 + 1).into());
+                }
+                let storage = Arc::as_ptr(&row.pixel_chunks);
+                let mut expected: Vec<u8> = row.iter().map(|pixel| pixel.value).collect();
+                expected[start..end].reverse();
+                row.reverse_range(start..end);
+                let pixels: Vec<u8> = row.iter().map(|pixel| pixel.value).collect();
+                assert_eq!(pixels, expected);
+                assert_eq!(Arc::as_ptr(&row.pixel_chunks), storage);
+            }
+        }
+    }
+
+    #[test]
+    fn can_compare_visible_pixels() {
+        use std::collections::hash_map::DefaultHasher;
+
+        let hash = |row: &PixelRow<Pixel4>| {
+            let mut hasher = DefaultHasher::new();
+            row.hash(&mut hasher);
+            hasher.finish()
+        };
+        let mut row: PixelRow<Pixel4> = PixelRow::new(6);
+        row.pad_left = 1;
+        row.pad_right = 3;
+        for i in 0..2 {
+            row.set_pixel(i, (i as u8 + 1).into());
+        }
+        let mut other: PixelRow<Pixel4> = PixelRow::new(2);
+        other.fill_range(0..2, 1.into());
+        other.set_pixel(1, 2.into());
+        assert_eq!(row, other);
+        assert_eq!(hash(&row), hash(&other));
+
+        // pixels hidden by padding don't matter
+        row[0] = Pixel4 { value: 0xF1 };
+        row[2] = Pixel4 { value: 0xFF };
+        assert_eq!(row, other);
+        assert_eq!(hash(&row), hash(&other));
+
+        other.set_pixel(1, 3.into());
+        assert_ne!(row, other);
+        assert_ne!(row, PixelRow::new(3));
+    }
+
+    #[test]
+    fn clones_share_storage_until_changed() {
+        let mut row: PixelRow<Pixel4> = PixelRow::new_with(8, 0x11.into());
+        let snapshot = row.clone();
+        assert!(row.shares_storage_with(&snapshot));
+        row.fill_range(2..4, 3.into());
+        assert!(!row.shares_storage_with(&snapshot));
+        assert_eq!(row[1].value, 0x33);
+        assert_eq!(snapshot[1].value, 0x11);
+
+        // an unshared row is written in place
+        let chunks = Arc::as_ptr(&row.pixel_chunks);
+        row.set_pixel(0, 2.into());
+        assert_eq!(Arc::as_ptr(&row.pixel_chunks), chunks);
+
+        let mut copy = snapshot.clone();
+        copy[0] = 0xFF.into();
+        assert!(!copy.shares_storage_with(&snapshot));
+        assert_eq!(snapshot[0].value, 0x11);
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn writes_that_change_nothing_keep_sharing_storage() {
+        let mut row: PixelRow<Pixel4> = PixelRow::new_with(8, 0x11.into());
+        let snapshot = row.clone();
+        row.fill_range(3..3, 2.into());
+        row.fill_range(5..2, 2.into());
+        row.fill_range(8..20, 2.into());
+        row.fill_range_masked(0..8, 2.into(), 0.into());
+        row.set_pixel_masked(1, 2.into(), 0.into());
+        row.copy_from(8, &snapshot, 0..4);
+        row.replace_colour(4..4, 1.into(), 2.into());
+        row.shift_range(2..2, 1, 0.into());
+        assert_eq!(
+            row.fill_range_with_aligned_chunks(2..2, &[0x22.into()]),
+            Some(0)
+        );
+        assert_eq!(row.iter_range_mut(9..12).count(), 0);
+        assert_eq!(row.chunks_in_mut(8..12).count(), 0);
+        assert!(row.shares_storage_with(&snapshot));
+        row.fill_range_masked(0..8, 2.into(), 0x2.into());
+        assert!(!row.shares_storage_with(&snapshot));
+    }
+
+    #[test]
+    fn can_iterate_over_row_in_reverse() {
+        let mut row: PixelRow<Pixel4> = PixelRow::new(12);
+        row.pad_left = 1;
+        for i in 0..11 {
+            row.set_pixel(i, (i as u8).into());
+        }
+        let pixels: Vec<u8> = row.iter().rev().map(|pixel| pixel.value).collect();
+        assert_eq!(pixels, [10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0]);
+
+        let mut iter = row.iter();
+        assert_eq!(iter.len(), 11);
+        assert_eq!(iter.next(), Some(0.into()));
+        assert_eq!(iter.next_back(), Some(10.into()));
+        assert_eq!(iter.nth(3), Some(4.into()));
+        assert_eq!(iter.nth_back(2), Some(7.into()));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.nth(5), None);
+        assert_eq!(iter.next_back(), None);
+    }
+
+    #[test]
+    fn can_iterate_over_range_of_row() {
+        let mut row: PixelRow<Pixel2> = PixelRow::new(10);
+        row.pad_left = 2;
+        for i in 0..8 {
+            row.set_pixel(i, (i as u8 % 4).into());
+        }
+        let pixels: Vec<u8> = row.iter_range(3..6).map(|pixel| pixel.value).collect();
+        assert_eq!(pixels, [3, 0, 1]);
+        // ranges are clipped to the row
+        assert_eq!(row.iter_range(6..20).len(), 2);
+        assert_eq!(row.iter_range(12..20).len(), 0);
+
+        for pixel in row.iter_range_mut(2..7).rev().step_by(2) {
+            pixel.set(3.into());
+        }
+        let pixels: Vec<u8> = row.iter().map(|pixel| pixel.value).collect();
+        assert_eq!(pixels, [0, 1, 3, 3, 3, 1, 3, 3]);
+        assert_eq!(
+            row.iter_range_mut(0..8).nth_back(7).unwrap().get(),
+            0.into()
+        );
+
+        let row: PixelRow<Pixel8> = PixelRow::new(0);
+        assert_eq!(row.iter().next(), None);
+    }
+
+    #[test]
+    fn can_change_pixels_with_mutable_iterator() {
+        let mut row: PixelRow<Pixel4> = PixelRow::new_with(10, 0xFF.into());
+        row.pad_left = 1;
+        row.pad_right = 1;
+        for (i, pixel) in row.iter_mut().enumerate() {
+            assert_eq!(pixel.get(), 0xF.into());
+            pixel.set((i as u8).into());
+        }
+        for pixel in &mut row {
+            let value = pixel.get().value;
+            pixel.set((value * 2).into());
+        }
+        for i in 0..8 {
+            assert_eq!(row.pixel(i), Some((i as u8 * 2).into()));
+        }
+        // padding is untouched
+        assert_eq!(row.pixel_chunks[0].value, 0xF0);
+        assert_eq!(row.pixel_chunks[4].value, 0xEF);
+        assert_eq!(row.iter_mut().count(), 8);
+
+        let mut row: PixelRow<Pixel1> = PixelRow::new(12);
+        for pixel in row.iter_mut().step_by(3) {
+            pixel.set(1.into());
+        }
+        assert_eq!(row.pixel_chunks[0].value, 0b1001_0010);
+        assert_eq!(row.pixel_chunks[1].value, 0b0100_0000);
+    }
+
     #[test]
     fn can_iterate_over_padded_row() {
         let mut row: PixelRow<Pixel4> = PixelRow::new(14);
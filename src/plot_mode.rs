@@ -0,0 +1,72 @@
+use crate::pixel_formats::PixelChunk;
+
+// Logical operation used when writing pixels into a row
+// These match the basic GCOL plot modes used by Acorn's VDU system
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum PlotMode {
+    // replace the destination with the source
+    #[default]
+    Set,
+    // OR the source into the destination
+    Or,
+    // AND the source into the destination
+    And,
+    // XOR the source into the destination
+    Xor,
+    // invert the destination, ignoring the source
+    Invert,
+    // replace the destination with the inverse of the source
+    Not,
+}
+
+impl PlotMode {
+    // combine whole chunks
+    #[inline]
+    pub fn apply<T: PixelChunk>(self, dest: T, source: T) -> T {
+        match self {
+            PlotMode::Set => source,
+            PlotMode::Or => dest | source,
+            PlotMode::And => dest & source,
+            PlotMode::Xor => dest ^ source,
+            PlotMode::Invert => !dest,
+            PlotMode::Not => !source,
+        }
+    }
+
+    // combine chunks, only changing the destination bits that are set in `mask`
+    #[inline]
+    pub fn apply_masked<T: PixelChunk>(self, dest: T, source: T, mask: T) -> T {
+        (dest & !mask) | (self.apply(dest, source) & mask)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel_formats::Pixel4;
+
+    #[test]
+    fn can_apply_plot_modes() {
+        let dest = Pixel4 { value: 0xC3 };
+        let source = Pixel4 { value: 0x5A };
+        assert_eq!(PlotMode::Set.apply(dest, source).value, 0x5A);
+        assert_eq!(PlotMode::Or.apply(dest, source).value, 0xDB);
+        assert_eq!(PlotMode::And.apply(dest, source).value, 0x42);
+        assert_eq!(PlotMode::Xor.apply(dest, source).value, 0x99);
+        assert_eq!(PlotMode::Invert.apply(dest, source).value, 0x3C);
+        assert_eq!(PlotMode::Not.apply(dest, source).value, 0xA5);
+    }
+
+    #[test]
+    fn can_apply_plot_modes_with_mask() {
+        let dest = Pixel4 { value: 0xC3 };
+        let source = Pixel4 { value: 0x5A };
+        let mask = Pixel4 { value: 0x0F };
+        assert_eq!(PlotMode::Set.apply_masked(dest, source, mask).value, 0xCA);
+        assert_eq!(PlotMode::Xor.apply_masked(dest, source, mask).value, 0xC9);
+        assert_eq!(
+            PlotMode::Invert.apply_masked(dest, source, mask).value,
+            0xCC
+        );
+    }
+}
@@ -0,0 +1,378 @@
+use std::mem::size_of;
+use std::ops::Range;
+
+use crate::frame_buffer::FrameBuffer;
+use crate::pixel_formats::PixelChunk;
+use crate::pixel_row::PixelRow;
+use crate::plot_mode::PlotMode;
+
+// A run of pixels in an encoded row
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Run<T: PixelChunk> {
+    // `length` copies of a single pixel value
+    Fill { length: u16, pixel: T },
+    // `length` pixels stored packed in the row's literal chunks, starting on a fresh chunk
+    Literal { length: u16 },
+}
+
+impl<T: PixelChunk<PixelType = T>> Run<T> {
+    pub fn length(&self) -> usize {
+        match *self {
+            Run::Fill { length, .. } | Run::Literal { length } => length as usize,
+        }
+    }
+}
+
+// The pixels to write for a run while decoding
+enum RunPixels<'a, T> {
+    Fill(T),
+    Literal(&'a [T]),
+}
+
+// The visible pixels of a row, stored as runs of identical pixels
+// Runs are found a chunk at a time, only looking at individual pixels where a chunk isn't all one colour
+// Runs too short to save space are gathered into literal runs of packed chunks instead, so a
+// row with little repetition takes barely more room than the row itself
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RleRow<T: PixelChunk> {
+    width: usize,
+    runs: Vec<Run<T>>,
+    literals: Vec<T>,
+}
+
+// the number of chunks the shortest fill run covers
+const MIN_FILL_CHUNKS: usize = 8;
+
+// Builds the runs of a row, deciding which runs of identical pixels are worth a fill run
+struct Encoder<T: PixelChunk> {
+    runs: Vec<Run<T>>,
+    literals: Vec<T>,
+    literal_length: usize,
+}
+
+impl<T: PixelChunk<PixelType = T>> Encoder<T> {
+    // the shortest run stored as a fill, which must cover enough chunks to be worth more than
+    // its own run plus the literal run that follows it
+    // this depends only on the pixel format, so a row encodes the same way on every target
+    fn min_fill() -> usize {
+        MIN_FILL_CHUNKS * T::pixels()
+    }
+
+    // the longest literal run, kept to whole chunks so each literal run starts on a fresh chunk
+    fn max_literal() -> usize {
+        u16::MAX as usize / T::pixels() * T::pixels()
+    }
+
+    fn add(&mut self, pixel: T, count: usize) {
+        if count >= Self::min_fill() {
+            self.end_literal();
+            let mut count = count;
+            while count > 0 {
+                let length = count.min(u16::MAX as usize);
+                self.runs.push(Run::Fill {
+                    length: length as u16,
+                    pixel,
+                });
+                count -= length;
+            }
+        } else {
+            for _ in 0..count {
+                let index = self.literal_length % T::pixels();
+                if index == 0 {
+                    self.literals.push(T::default());
+                }
+                if let Some(chunk) = self.literals.last_mut() {
+                    chunk.set_pixel(index, pixel);
+                }
+                self.literal_length += 1;
+                if self.literal_length == Self::max_literal() {
+                    self.end_literal();
+                }
+            }
+        }
+    }
+
+    fn end_literal(&mut self) {
+        if self.literal_length > 0 {
+            self.runs.push(Run::Literal {
+                length: self.literal_length as u16,
+            });
+            self.literal_length = 0;
+        }
+    }
+}
+
+impl<T: PixelChunk<PixelType = T>> RleRow<T> {
+    pub fn encode(row: &PixelRow<T>) -> RleRow<T> {
+        let width = row.width();
+        let mut encoder = Encoder {
+            runs: Vec::new(),
+            literals: Vec::new(),
+            literal_length: 0,
+        };
+        // the run of identical pixels found so far
+        let mut current: Option<(T, usize)> = None;
+        for (chunk_index, chunk) in row.visible_chunks().enumerate() {
+            let pixels = (width - chunk_index * T::pixels()).min(T::pixels());
+            // a whole chunk continuing the current run can be added in one go
+            if let Some((pixel, count)) = current.as_mut() {
+                if pixels == T::pixels() && chunk == T::filled_pixel(*pixel) {
+                    *count += pixels;
+                    continue;
+                }
+            }
+            for index in 0..pixels {
+                let pixel = chunk.get_pixel(index).unwrap_or_default();
+                match current.as_mut() {
+                    Some((current_pixel, count)) if *current_pixel == pixel => *count += 1,
+                    _ => {
+                        if let Some((current_pixel, count)) = current {
+                            encoder.add(current_pixel, count);
+                        }
+                        current = Some((pixel, 1));
+                    }
+                }
+            }
+        }
+        if let Some((pixel, count)) = current {
+            encoder.add(pixel, count);
+        }
+        encoder.end_literal();
+        RleRow {
+            width,
+            runs: encoder.runs,
+            literals: encoder.literals,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn runs(&self) -> &[Run<T>] {
+        &self.runs
+    }
+
+    // the packed pixels of the literal runs, in order
+    pub fn literals(&self) -> &[T] {
+        &self.literals
+    }
+
+    // the number of bytes taken by the runs and literal chunks
+    pub fn encoded_size(&self) -> usize {
+        self.runs.len() * size_of::<Run<T>>() + self.literals.len() * size_of::<T>()
+    }
+
+    pub fn decode(&self) -> PixelRow<T> {
+        let mut row = PixelRow::new(self.width);
+        self.decode_into(&mut row, 0);
+        row
+    }
+
+    // write the pixels straight into `row`, starting at `start`, clipped to the width of the row
+    pub fn decode_into(&self, row: &mut PixelRow<T>, start: usize) {
+        let width = row.width();
+        self.decode_with(start, width, |range, pixels| match pixels {
+            RunPixels::Fill(pixel) => row.fill_range(range, pixel),
+            RunPixels::Literal(chunks) => {
+                row.fill_range_with_chunks(range, chunks);
+            }
+        });
+    }
+
+    // calls `write` with the destination range of each run, clipped to `width`, and its pixels
+    fn decode_with<F>(&self, start: usize, width: usize, mut write: F)
+    where
+        F: FnMut(Range<usize>, RunPixels<'_, T>),
+    {
+        let mut x = start;
+        let mut literal_index = 0;
+        for run in self.runs.iter() {
+            if x >= width {
+                break;
+            }
+            let end = (x + run.length()).min(width);
+            match *run {
+                Run::Fill { pixel, .. } => write(x..end, RunPixels::Fill(pixel)),
+                Run::Literal { length } => {
+                    let chunks = (length as usize).div_ceil(T::pixels());
+                    write(
+                        x..end,
+                        RunPixels::Literal(&self.literals[literal_index..literal_index + chunks]),
+                    );
+                    literal_index += chunks;
+                }
+            }
+            x = end;
+        }
+    }
+}
+
+// The visible pixels of a framebuffer, stored as a run-length encoded row for each row
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RleFrameBuffer<T: PixelChunk> {
+    width: usize,
+    rows: Vec<RleRow<T>>,
+}
+
+impl<T: PixelChunk<PixelType = T>> RleFrameBuffer<T> {
+    pub fn encode(buffer: &FrameBuffer<T>) -> RleFrameBuffer<T> {
+        RleFrameBuffer {
+            width: buffer.width(),
+            rows: (0..buffer.height())
+                .filter_map(|y| buffer.row(y).map(RleRow::encode))
+                .collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn row(&self, y: usize) -> Option<&RleRow<T>> {
+        self.rows.get(y)
+    }
+
+    pub fn decode(&self) -> FrameBuffer<T> {
+        let mut buffer = FrameBuffer::new(self.width, self.height());
+        self.decode_into(&mut buffer, 0, 0);
+        buffer
+    }
+
+    // write the pixels into `buffer` with the top left at `x`, `y`, clipped to the buffer
+    // the buffer's write mask and clip mask apply
+    pub fn decode_into(&self, buffer: &mut FrameBuffer<T>, x: usize, y: usize) {
+        let width = buffer.width();
+        for (row, dest_y) in self.rows.iter().zip(y..buffer.height()) {
+            buffer.write_row(dest_y, |dest, write_mask| {
+                row.decode_with(x, width, |range, pixels| match pixels {
+                    RunPixels::Fill(pixel) => {
+                        dest.write_range(range, T::filled_pixel(pixel), PlotMode::Set, write_mask)
+                    }
+                    RunPixels::Literal(chunks) => {
+                        dest.write_range_with_chunks(range, chunks, PlotMode::Set, write_mask);
+                    }
+                });
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel_formats::{Pixel1, Pixel4, Pixel8};
+
+    #[test]
+    fn can_encode_row() {
+        let mut row: PixelRow<Pixel4> = PixelRow::new(60);
+        row.fill_range(0..40, 3.into());
+        row.fill_range(5..6, 1.into());
+        row.fill_range(40..60, 2.into());
+        let rle = RleRow::encode(&row);
+        assert_eq!(rle.width(), 60);
+        // the short runs at the start are stored as packed pixels
+        assert_eq!(
+            rle.runs(),
+            [
+                Run::Literal { length: 6 },
+                Run::Fill {
+                    length: 34,
+                    pixel: 3.into()
+                },
+                Run::Fill {
+                    length: 20,
+                    pixel: 2.into()
+                },
+            ]
+        );
+        assert_eq!(rle.literals().len(), 3);
+        assert_eq!(rle.decode(), row);
+
+        // long runs are split
+        let row: PixelRow<Pixel1> = PixelRow::new_with(70000, 0xFF.into());
+        let rle = RleRow::encode(&row);
+        assert_eq!(rle.runs().len(), 2);
+        assert_eq!(rle.runs()[1].length(), 4465);
+        assert_eq!(rle.decode(), row);
+    }
+
+    #[test]
+    fn encoding_never_grows_much() {
+        // a checkerboard has no runs at all
+        let row: PixelRow<Pixel1> = PixelRow::new_with(640, 0xAA.into());
+        let rle = RleRow::encode(&row);
+        assert_eq!(rle.runs(), [Run::Literal { length: 640 }]);
+        assert!(rle.encoded_size() <= 80 + size_of::<Run<Pixel1>>());
+        assert_eq!(rle.decode(), row);
+
+        // runs just long enough to be worth a fill, broken by single pixels
+        let mut row: PixelRow<Pixel8> = PixelRow::new(640);
+        for x in (0..640).step_by(9) {
+            row.set_pixel(x, ((x % 7) as u8 + 1).into());
+        }
+        let rle = RleRow::encode(&row);
+        assert!(rle.encoded_size() <= 640 + size_of::<Run<Pixel8>>());
+        assert_eq!(rle.decode(), row);
+
+        // literal runs longer than a run length can hold are split on a chunk boundary
+        let row: PixelRow<Pixel4> = PixelRow::new_with(70000, 0x12.into());
+        let rle = RleRow::encode(&row);
+        assert_eq!(rle.runs()[0].length(), 65534);
+        assert!(rle.encoded_size() <= 35000 + 2 * size_of::<Run<Pixel4>>());
+        assert_eq!(rle.decode(), row);
+    }
+
+    #[test]
+    fn fills_cover_a_fixed_number_of_chunks() {
+        let mut row: PixelRow<Pixel4> = PixelRow::new(31);
+        row.fill_range(0..15, 1.into());
+        row.fill_range(15..31, 2.into());
+        let rle = RleRow::encode(&row);
+        assert_eq!(
+            rle.runs(),
+            [
+                Run::Literal { length: 15 },
+                Run::Fill {
+                    length: 16,
+                    pixel: 2.into()
+                },
+            ]
+        );
+        assert_eq!(rle.decode(), row);
+    }
+
+    #[test]
+    fn can_decode_into_row() {
+        let mut source: PixelRow<Pixel4> = PixelRow::new(6);
+        source.fill_range(2..6, 7.into());
+        let rle = RleRow::encode(&source);
+
+        let mut row: PixelRow<Pixel4> = PixelRow::new_with(10, 0x11.into());
+        rle.decode_into(&mut row, 7);
+        let pixels: Vec<u8> = row.iter().map(|pixel| pixel.value).collect();
+        assert_eq!(pixels, [1, 1, 1, 1, 1, 1, 1, 0, 0, 7]);
+    }
+
+    #[test]
+    fn can_encode_frame_buffer() {
+        let mut buffer: FrameBuffer<Pixel8> = FrameBuffer::new(5, 3);
+        buffer.fill_range(1, 1..4, 9.into());
+        let rle = RleFrameBuffer::encode(&buffer);
+        assert_eq!(rle.height(), 3);
+        assert_eq!(rle.row(0).unwrap().runs().len(), 1);
+        assert_eq!(rle.row(1).unwrap().runs(), [Run::Literal { length: 5 }]);
+        assert!(rle.decode() == buffer);
+
+        let mut dest: FrameBuffer<Pixel8> = FrameBuffer::new_with(4, 4, 1.into());
+        rle.decode_into(&mut dest, 2, 2);
+        assert_eq!(dest.pixel(2, 3), Some(0.into()));
+        assert_eq!(dest.pixel(3, 3), Some(9.into()));
+        assert_eq!(dest.pixel(1, 3), Some(1.into()));
+        assert_eq!(dest.pixel(2, 1), Some(1.into()));
+    }
+}
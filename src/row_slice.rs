@@ -0,0 +1,204 @@
+use std::ops::Range;
+
+use crate::pixel_formats::PixelChunk;
+use crate::pixel_row::{PixelMut, PixelRow, PixelRowIterator, PixelRowIteratorMut};
+use crate::plot_mode::PlotMode;
+
+// A borrowed window onto a range of pixels in a row
+// Indices are relative to the start of the slice, and reads outside the slice return nothing
+#[derive(Clone, Copy)]
+pub struct RowSlice<'a, T: PixelChunk> {
+    row: &'a PixelRow<T>,
+    start: usize,
+    width: usize,
+}
+
+// A mutable borrowed window onto a range of pixels in a row
+// Indices are relative to the start of the slice, and writes are clipped to the slice
+pub struct RowSliceMut<'a, T: PixelChunk> {
+    row: &'a mut PixelRow<T>,
+    start: usize,
+    width: usize,
+}
+
+// clips `range` to a slice of `width` pixels, and offsets it into row coordinates
+fn row_range(start: usize, width: usize, range: Range<usize>) -> Range<usize> {
+    let end = range.end.min(width);
+    let range_start = range.start.min(end);
+    start + range_start..start + end
+}
+
+impl<'a, T: PixelChunk<PixelType = T>> RowSlice<'a, T> {
+    pub(crate) fn new(row: &'a PixelRow<T>, range: Range<usize>) -> RowSlice<'a, T> {
+        let range = row_range(0, row.width(), range);
+        RowSlice {
+            row,
+            start: range.start,
+            width: range.len(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn pixel(&self, index: usize) -> Option<T> {
+        if index < self.width {
+            self.row.pixel(self.start + index)
+        } else {
+            None
+        }
+    }
+
+    // a narrower view within this slice
+    pub fn slice(&self, range: Range<usize>) -> RowSlice<'a, T> {
+        let range = row_range(self.start, self.width, range);
+        RowSlice {
+            row: self.row,
+            start: range.start,
+            width: range.len(),
+        }
+    }
+
+    pub fn iter(&self) -> PixelRowIterator<'a, T> {
+        self.row.iter_range(self.start..self.start + self.width)
+    }
+
+    pub fn to_row(&self) -> PixelRow<T> {
+        let mut row = PixelRow::new(self.width);
+        row.copy_from(0, self.row, self.start..self.start + self.width);
+        row
+    }
+}
+
+impl<'a, T: PixelChunk<PixelType = T>> IntoIterator for RowSlice<'a, T> {
+    type Item = T;
+    type IntoIter = PixelRowIterator<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T: PixelChunk<PixelType = T>> RowSliceMut<'a, T> {
+    pub(crate) fn new(row: &'a mut PixelRow<T>, range: Range<usize>) -> RowSliceMut<'a, T> {
+        let range = row_range(0, row.width(), range);
+        RowSliceMut {
+            row,
+            start: range.start,
+            width: range.len(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn pixel(&self, index: usize) -> Option<T> {
+        self.as_slice().pixel(index)
+    }
+
+    // set a pixel, ignoring indices outside the slice
+    pub fn set_pixel(&mut self, index: usize, pixel: T) {
+        if index < self.width {
+            self.row.set_pixel(self.start + index, pixel);
+        }
+    }
+
+    pub fn fill_range(&mut self, range: Range<usize>, pixel: T) {
+        self.plot_range(range, pixel, PlotMode::Set);
+    }
+
+    pub fn plot_range(&mut self, range: Range<usize>, pixel: T, mode: PlotMode) {
+        let range = row_range(self.start, self.width, range);
+        self.row.plot_range(range, pixel, mode);
+    }
+
+    // fill the whole slice
+    pub fn fill(&mut self, pixel: T) {
+        self.fill_range(0..self.width, pixel);
+    }
+
+    pub fn as_slice(&self) -> RowSlice<'_, T> {
+        RowSlice {
+            row: self.row,
+            start: self.start,
+            width: self.width,
+        }
+    }
+
+    // a narrower mutable view within this slice
+    pub fn slice_mut(&mut self, range: Range<usize>) -> RowSliceMut<'_, T> {
+        let range = row_range(self.start, self.width, range);
+        RowSliceMut {
+            row: self.row,
+            start: range.start,
+            width: range.len(),
+        }
+    }
+
+    pub fn iter(&self) -> PixelRowIterator<'_, T> {
+        self.row.iter_range(self.start..self.start + self.width)
+    }
+
+    pub fn iter_mut(&mut self) -> PixelRowIteratorMut<'_, T> {
+        self.row.iter_range_mut(self.start..self.start + self.width)
+    }
+}
+
+impl<'a, T: PixelChunk<PixelType = T>> IntoIterator for RowSliceMut<'a, T> {
+    type Item = PixelMut<'a, T>;
+    type IntoIter = PixelRowIteratorMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.row.iter_range_mut(self.start..self.start + self.width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel_formats::{Pixel1, Pixel4};
+
+    #[test]
+    fn can_read_through_row_slice() {
+        let mut row: PixelRow<Pixel4> = PixelRow::new(8);
+        for i in 0..8 {
+            row.set_pixel(i, (i as u8).into());
+        }
+        let slice = row.slice(2..6);
+        assert_eq!(slice.width(), 4);
+        assert_eq!(slice.pixel(0), Some(2.into()));
+        assert_eq!(slice.pixel(3), Some(5.into()));
+        assert_eq!(slice.pixel(4), None);
+        let pixels: Vec<u8> = slice.iter().map(|pixel| pixel.value).collect();
+        assert_eq!(pixels, [2, 3, 4, 5]);
+        let pixels: Vec<u8> = slice.slice(1..10).iter().map(|pixel| pixel.value).collect();
+        assert_eq!(pixels, [3, 4, 5]);
+        assert_eq!(slice.to_row().pixel(1), Some(3.into()));
+        let pixels: Vec<u8> = row
+            .slice(1..6)
+            .to_row()
+            .iter()
+            .map(|pixel| pixel.value)
+            .collect();
+        assert_eq!(pixels, [1, 2, 3, 4, 5]);
+        // slices are clipped to the row
+        assert_eq!(row.slice(6..20).width(), 2);
+    }
+
+    #[test]
+    fn can_write_through_row_slice() {
+        let mut row: PixelRow<Pixel1> = PixelRow::new(16);
+        let mut slice = row.slice_mut(3..11);
+        slice.fill_range(2..20, 1.into());
+        slice.set_pixel(0, 1.into());
+        slice.set_pixel(8, 1.into());
+        slice.slice_mut(4..6).fill(0.into());
+        for pixel in slice.iter_mut().rev().take(1) {
+            pixel.set(0.into());
+        }
+        assert_eq!(row[0].value, 0b0001_0110);
+        assert_eq!(row[1].value, 0b0100_0000);
+    }
+}